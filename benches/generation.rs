@@ -0,0 +1,25 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use hexlab::prelude::*;
+use std::hint::black_box;
+
+fn builder(radius: u16) -> MazeBuilder {
+    MazeBuilder::new().with_radius(radius).with_seed(12345)
+}
+
+/// Compares generating into a hash map backed [`Maze`] with a [`DenseMaze`].
+fn generation(c: &mut Criterion) {
+    let mut group = c.benchmark_group("generation");
+    group.sample_size(10);
+    for radius in [50, 200, 577] {
+        group.bench_with_input(BenchmarkId::new("maze", radius), &radius, |b, &radius| {
+            b.iter(|| black_box(builder(radius).build()));
+        });
+        group.bench_with_input(BenchmarkId::new("dense", radius), &radius, |b, &radius| {
+            b.iter(|| black_box(builder(radius).build_dense()));
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, generation);
+criterion_main!(benches);
@@ -1,5 +1,15 @@
-use crate::{errors::MazeBuilderError, GeneratorType, Maze};
+use crate::{
+    dense::Bounds,
+    errors::MazeBuilderError,
+    generator::{
+        add_loops, braid, components, create_rng, default_start, generate_backtracking,
+        generate_layered, generate_with_rooms, sorted_tiles, weave, Biased, StepLog, StepSink,
+    },
+    DenseMaze, DirectionBias, GenerationSteps, GeneratorType, LayeredMaze, Maze, MazeGenerator,
+    MazeShape, Topology,
+};
 use hexx::Hex;
+use rand::RngCore;
 
 /// A builder pattern for creating hexagonal mazes.
 ///
@@ -54,13 +64,28 @@ use hexx::Hex;
 ///     .build()
 ///     .expect("Failed to create maze");
 /// ```
+///
+/// Custom generators can be plugged in by implementing [`MazeGenerator`], see
+/// [`MazeBuilder::with_custom_generator`].
 #[allow(clippy::module_name_repetitions)]
 #[derive(Default)]
 pub struct MazeBuilder {
     radius: Option<u16>,
+    dimensions: Option<(u16, u16)>,
+    shape: MazeShape,
+    tiles: Option<Vec<Hex>>,
+    holes: Vec<Hex>,
+    rooms: Vec<(Vec<Hex>, usize)>,
+    topology: Topology,
     seed: Option<u64>,
     generator_type: GeneratorType,
+    bias: DirectionBias,
     start_position: Option<Hex>,
+    custom_generator: Option<Box<dyn MazeGenerator>>,
+    braid: Option<f32>,
+    loops: Option<(usize, usize)>,
+    weave: Option<f32>,
+    levels: Option<usize>,
 }
 
 impl MazeBuilder {
@@ -78,6 +103,8 @@ impl MazeBuilder {
     /// For example, a radius of 3 would create a maze with 3 tiles from center to edge,
     /// resulting in a total diameter of 7 tiles (3 + 1 + 3).
     ///
+    /// Other radius-based shapes are sized the same way, see [`MazeShape`].
+    ///
     /// # Arguments
     ///
     /// - `radius` - The number of tiles from the center to the edge of the hexagon.
@@ -88,6 +115,206 @@ impl MazeBuilder {
         self
     }
 
+    /// Sets the dimensions for rectangular maze shapes.
+    ///
+    /// Only used by shapes that are not radius-based, see [`MazeShape`].
+    ///
+    /// # Arguments
+    ///
+    /// - `width` - The number of columns.
+    /// - `height` - The number of rows.
+    #[inline]
+    #[must_use]
+    pub const fn with_dimensions(mut self, width: u16, height: u16) -> Self {
+        self.dimensions = Some((width, height));
+        self
+    }
+
+    /// Sets the shape of the maze.
+    ///
+    /// Defaults to [`MazeShape::Hexagon`].
+    ///
+    /// # Arguments
+    ///
+    /// - `shape` - The shape of the tile set to generate the maze on.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use hexlab::prelude::*;
+    ///
+    /// // Rectangular shapes need dimensions instead of a radius
+    /// let result = MazeBuilder::new()
+    ///     .with_shape(MazeShape::PointyRectangle)
+    ///     .with_radius(5)
+    ///     .build();
+    /// assert_eq!(result, Err(MazeBuilderError::NoDimensions));
+    ///
+    /// let maze = MazeBuilder::new()
+    ///     .with_shape(MazeShape::PointyRectangle)
+    ///     .with_dimensions(16, 9)
+    ///     .build()
+    ///     .expect("Failed to create maze");
+    /// assert_eq!(maze.count(), 144);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub const fn with_shape(mut self, shape: MazeShape) -> Self {
+        self.shape = shape;
+        self
+    }
+
+    /// Sets the exact tiles to generate the maze on, for example a level painted in an editor.
+    ///
+    /// The tiles take precedence over the shape, radius and dimensions. Duplicate positions
+    /// are ignored.
+    ///
+    /// # Arguments
+    ///
+    /// - `tiles` - The positions of the tiles, which must all be connected to each other.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use hexlab::prelude::*;
+    ///
+    /// let tiles = (0..10).map(|x| Hex::new(x, 0)).chain((0..10).map(|x| Hex::new(x, 1)));
+    /// let maze = MazeBuilder::new()
+    ///     .with_tiles(tiles)
+    ///     .with_seed(12345)
+    ///     .build()
+    ///     .expect("Failed to create maze");
+    /// assert_eq!(maze.count(), 20);
+    ///
+    /// // Tiles with a gap between them can not form a single maze
+    /// let result = MazeBuilder::new()
+    ///     .with_tiles([Hex::new(0, 0), Hex::new(5, 0)])
+    ///     .build();
+    /// assert!(matches!(result, Err(MazeBuilderError::DisconnectedTiles(_))));
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn with_tiles(mut self, tiles: impl IntoIterator<Item = Hex>) -> Self {
+        self.tiles = Some(tiles.into_iter().collect());
+        self
+    }
+
+    /// Excludes tiles from the maze, leaving holes such as lakes or pillars to route around.
+    ///
+    /// The holes are removed from the tiles of the shape, or from the tiles set with
+    /// [`MazeBuilder::with_tiles`]. Positions outside the maze are ignored. Calling this again
+    /// adds more holes.
+    ///
+    /// # Arguments
+    ///
+    /// - `holes` - The positions of the tiles to exclude.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use hexlab::prelude::*;
+    ///
+    /// let maze = MazeBuilder::new()
+    ///     .with_radius(4)
+    ///     .with_holes([Hex::new(1, 0), Hex::new(2, 0), Hex::new(-2, 1)])
+    ///     .build()
+    ///     .expect("Failed to create maze");
+    /// assert_eq!(maze.count(), 58);
+    /// assert!(maze.get(&Hex::new(1, 0)).is_none());
+    ///
+    /// // The start position can not be placed in a hole
+    /// let result = MazeBuilder::new()
+    ///     .with_radius(4)
+    ///     .with_holes([Hex::new(1, 0)])
+    ///     .with_start_position(Hex::new(1, 0))
+    ///     .build();
+    /// assert_eq!(result, Err(MazeBuilderError::InvalidStartPosition(Hex::new(1, 0))));
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn with_holes(mut self, holes: impl IntoIterator<Item = Hex>) -> Self {
+        self.holes.extend(holes);
+        self
+    }
+
+    /// Reserves an open room, with no walls between its tiles, connected to the surrounding
+    /// maze through doorways.
+    ///
+    /// The corridors around the rooms are generated with the configured generator. Every room
+    /// gets at least one doorway, and more if it is needed to keep the maze connected, for
+    /// example when the room splits the corridors in two. Tiles outside the maze are ignored,
+    /// and tiles shared with an earlier room belong to that room.
+    ///
+    /// # Arguments
+    ///
+    /// - `tiles` - The positions of the room's tiles, such as [`Hex::range`] for a hexagonal
+    ///   room.
+    /// - `doorways` - The number of doorways to connect the room through.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use hexlab::prelude::*;
+    ///
+    /// let center = Hex::new(3, -1);
+    /// let maze = MazeBuilder::new()
+    ///     .with_radius(8)
+    ///     .with_seed(12345)
+    ///     .with_room(center.range(2), 3)
+    ///     .build()
+    ///     .expect("Failed to create maze");
+    ///
+    /// // The room is open inside
+    /// let walls = maze.get_walls(&center).unwrap();
+    /// assert!(walls.is_empty());
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn with_room(mut self, tiles: impl IntoIterator<Item = Hex>, doorways: usize) -> Self {
+        self.rooms.push((tiles.into_iter().collect(), doorways));
+        self
+    }
+
+    /// Sets how the edges of the maze connect, for example to wrap around them.
+    ///
+    /// With a wrapping topology, neighbors are looked up across the seams by the generators
+    /// and [`Maze::find_path`], and walls across a seam are carved on both sides. The size of
+    /// the topology must match the shape, so that every tile stays in place when wrapped.
+    ///
+    /// Defaults to [`Topology::Bounded`].
+    ///
+    /// # Arguments
+    ///
+    /// - `topology` - The topology of the maze.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use hexlab::prelude::*;
+    ///
+    /// let maze = MazeBuilder::new()
+    ///     .with_shape(MazeShape::FlatRectangle)
+    ///     .with_dimensions(8, 6)
+    ///     .with_topology(Topology::FlatRectangle { width: 8, height: 6 })
+    ///     .with_seed(12345)
+    ///     .build()
+    ///     .expect("Failed to create maze");
+    /// assert!(maze.topology().is_wrapping());
+    ///
+    /// // A topology smaller than the shape would move tiles on top of each other
+    /// let result = MazeBuilder::new()
+    ///     .with_radius(5)
+    ///     .with_topology(Topology::Hexagon { radius: 3 })
+    ///     .build();
+    /// assert!(matches!(result, Err(MazeBuilderError::InvalidTopology(_))));
+    /// ```
+    #[inline]
+    #[must_use]
+    pub const fn with_topology(mut self, topology: Topology) -> Self {
+        self.topology = topology;
+        self
+    }
+
     /// Sets the random seed for maze generation.
     ///
     /// Using the same seed will produce identical mazes, allowing for reproducible results.
@@ -115,6 +342,52 @@ impl MazeBuilder {
         self.generator_type = generator_type;
         self
     }
+
+    /// Sets the bias weighting the directions the generator carves in, see [`DirectionBias`].
+    ///
+    /// Only applies to the built-in generators that support it, not to custom generators.
+    ///
+    /// Defaults to [`DirectionBias::Uniform`].
+    ///
+    /// # Arguments
+    ///
+    /// - `bias` - The bias of the carving directions.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use hexlab::prelude::*;
+    ///
+    /// let maze = MazeBuilder::new()
+    ///     .with_radius(5)
+    ///     .with_seed(12345)
+    ///     .with_bias(DirectionBias::Straight { weight: 3.0 })
+    ///     .build()
+    ///     .expect("Failed to create maze");
+    /// ```
+    #[inline]
+    #[must_use]
+    pub const fn with_bias(mut self, bias: DirectionBias) -> Self {
+        self.bias = bias;
+        self
+    }
+
+    /// Sets a custom generator algorithm for maze creation.
+    ///
+    /// The custom generator takes precedence over the one set with
+    /// [`MazeBuilder::with_generator`]. [`MazeBuilder::build_steps`] replays it in tile order
+    /// unless it overrides [`MazeGenerator::generate_steps`].
+    ///
+    /// # Arguments
+    ///
+    /// - `generator` - The maze generation algorithm to use.
+    #[inline]
+    #[must_use]
+    pub fn with_custom_generator(mut self, generator: impl MazeGenerator + 'static) -> Self {
+        self.custom_generator = Some(Box::new(generator));
+        self
+    }
+
     /// Sets the starting position for maze generation.
     ///
     /// # Arguments
@@ -127,11 +400,125 @@ impl MazeBuilder {
         self
     }
 
+    /// Removes a fraction of the dead ends after generation, creating loops.
+    ///
+    /// See [`Maze::braid`] for details. The dead ends are picked with the same seed as the
+    /// generation, so the result stays reproducible.
+    ///
+    /// # Arguments
+    ///
+    /// - `fraction` - The fraction of dead ends to remove, between `0.0` and `1.0`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use hexlab::prelude::*;
+    ///
+    /// let maze = MazeBuilder::new()
+    ///     .with_radius(5)
+    ///     .with_seed(12345)
+    ///     .with_braid(1.0)
+    ///     .build()
+    ///     .expect("Failed to create maze");
+    /// assert!(maze.dead_ends().is_empty());
+    /// ```
+    #[inline]
+    #[must_use]
+    pub const fn with_braid(mut self, fraction: f32) -> Self {
+        self.braid = Some(fraction);
+        self
+    }
+
+    /// Opens extra walls after generation, each creating a loop of at least `min_length` tiles.
+    ///
+    /// See [`Maze::add_loops`] for details. Loops are added before braiding, if both are
+    /// configured.
+    ///
+    /// # Arguments
+    ///
+    /// - `count` - The number of walls to open.
+    /// - `min_length` - The minimum number of tiles in each new loop.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use hexlab::prelude::*;
+    ///
+    /// let maze = MazeBuilder::new()
+    ///     .with_radius(5)
+    ///     .with_seed(12345)
+    ///     .with_loops(5, 8)
+    ///     .build()
+    ///     .expect("Failed to create maze");
+    /// ```
+    #[inline]
+    #[must_use]
+    pub const fn with_loops(mut self, count: usize, min_length: usize) -> Self {
+        self.loops = Some((count, min_length));
+        self
+    }
+
+    /// Turns a fraction of the straight corridors into crossings, with another passage
+    /// tunneling under them after generation.
+    ///
+    /// Each tunnel replaces another route between the tiles it connects, so perfect mazes stay
+    /// perfect. Crossings are added before loops and braiding, if those are configured. See
+    /// [`Tile::under_passage`] for how crossings are represented.
+    ///
+    /// [`Tile::under_passage`]: crate::Tile::under_passage
+    ///
+    /// # Arguments
+    ///
+    /// - `fraction` - The fraction of straight corridors to cross, between `0.0` and `1.0`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use hexlab::prelude::*;
+    ///
+    /// let maze = MazeBuilder::new()
+    ///     .with_radius(8)
+    ///     .with_seed(12345)
+    ///     .with_weave(0.5)
+    ///     .build()
+    ///     .expect("Failed to create maze");
+    /// assert!(maze.values().any(|tile| tile.under_passage().is_some()));
+    /// ```
+    #[inline]
+    #[must_use]
+    pub const fn with_weave(mut self, fraction: f32) -> Self {
+        self.weave = Some(fraction);
+        self
+    }
+
+    /// Sets the number of levels of a layered maze, see [`MazeBuilder::build_layered`].
+    ///
+    /// Defaults to a single level. Ignored by [`MazeBuilder::build`] and
+    /// [`MazeBuilder::build_steps`].
+    ///
+    /// # Arguments
+    ///
+    /// - `count` - The number of levels stacked on top of each other.
+    #[inline]
+    #[must_use]
+    pub const fn with_levels(mut self, count: usize) -> Self {
+        self.levels = Some(count);
+        self
+    }
+
     /// Builds the hexagonal maze based on the configured parameters.
     ///
     /// # Errors
     ///
-    /// Returns [`MazeBuilderError::NoRadius`] if no radius is specified.
+    /// Returns [`MazeBuilderError::NoRadius`] if no radius is specified for a radius-based shape.
+    /// Returns [`MazeBuilderError::NoDimensions`] if no dimensions are specified for a
+    /// rectangular shape.
+    /// Returns [`MazeBuilderError::DisconnectedTiles`] if the tiles set with
+    /// [`MazeBuilder::with_tiles`] are not all connected.
+    /// Returns [`MazeBuilderError::GenerationError`] if the holes set with
+    /// [`MazeBuilder::with_holes`] cut off tiles from the start position.
+    /// Returns [`MazeBuilderError::InvalidTopology`] if the topology set with
+    /// [`MazeBuilder::with_topology`] does not fit the tiles.
     /// Returns [`MazeBuilderError::InvalidStartPosition`] if the start position is outside maze bounds.
     ///
     /// # Examples
@@ -153,8 +540,234 @@ impl MazeBuilder {
     /// assert!(!maze.is_empty());
     /// ```
     pub fn build(self) -> Result<Maze, MazeBuilderError> {
-        let radius = self.radius.ok_or(MazeBuilderError::NoRadius)?;
-        let mut maze = create_hex_maze(radius);
+        let mut maze = self.create_maze()?;
+
+        if !maze.is_empty() {
+            let mut rng = create_rng(self.seed);
+            self.generate(&mut maze, &mut rng);
+        }
+
+        Ok(maze)
+    }
+
+    /// Builds a layered maze, stacking levels of the same tiles and generating a single maze
+    /// across all of them.
+    ///
+    /// Levels are generated together with randomized Kruskal's algorithm, which treats stairs
+    /// between tiles at the same position on consecutive levels like any other passage. The
+    /// result is a spanning tree of every tile on every level, with as many stairs as the tree
+    /// happens to use, while a level on its own may be split into several parts. The generator
+    /// type and direction bias are not used.
+    ///
+    /// Weave crossings, loops and braiding are applied to each level on its own. Crossings are
+    /// only placed where the passage they replace is on the same level, keeping the maze a
+    /// spanning tree.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`MazeBuilder::build`].
+    /// Returns [`MazeBuilderError::GenerationError`] if rooms or a custom generator are set,
+    /// which layered mazes do not support.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use hexlab::prelude::*;
+    ///
+    /// let maze = MazeBuilder::new()
+    ///     .with_radius(3)
+    ///     .with_levels(3)
+    ///     .with_seed(12345)
+    ///     .build_layered()
+    ///     .expect("Failed to create maze");
+    ///
+    /// assert_eq!(maze.level_count(), 3);
+    /// assert_eq!(maze.count(), 3 * 37);
+    /// assert!(maze.stairs().iter().any(|&(_, level)| level == 1));
+    /// ```
+    pub fn build_layered(self) -> Result<LayeredMaze, MazeBuilderError> {
+        if self.custom_generator.is_some() {
+            return Err(MazeBuilderError::GenerationError(
+                "custom generators are not supported by layered mazes".to_string(),
+            ));
+        }
+        if !self.rooms.is_empty() {
+            return Err(MazeBuilderError::GenerationError(
+                "rooms are not supported by layered mazes".to_string(),
+            ));
+        }
+        let maze = self.create_maze()?;
+
+        let mut rng = create_rng(self.seed);
+        let mut layered = (0..self.levels.unwrap_or(1))
+            .map(|_| maze.clone())
+            .collect::<LayeredMaze>();
+        generate_layered(&mut layered, &mut rng);
+        for index in 0..layered.level_count() {
+            if let Some(level) = layered.level_mut(index) {
+                self.post_process(level, &mut rng, &mut ());
+            }
+        }
+
+        Ok(layered)
+    }
+
+    /// Builds the hexagonal maze into a [`DenseMaze`], for boards too large for [`Maze`].
+    ///
+    /// The default recursive backtracking generator runs directly on the dense storage, creating
+    /// the tiles of the shape without ever storing them in a [`Maze`]. Other generators, custom
+    /// tiles, holes, rooms, loops and braiding build a [`Maze`] first and convert it, producing
+    /// the same maze as [`MazeBuilder::build`] either way.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`MazeBuilder::build`].
+    /// Returns [`MazeBuilderError::GenerationError`] if weave crossings are configured, as
+    /// [`DenseMaze`] does not store them, or if the bounding parallelogram of the tiles holds
+    /// more than 64 positions per tile.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use hexlab::prelude::*;
+    ///
+    /// let builder = || MazeBuilder::new().with_radius(10).with_seed(12345);
+    /// let dense = builder().build_dense().expect("Failed to create maze");
+    /// let maze = builder().build().expect("Failed to create maze");
+    ///
+    /// assert_eq!(Maze::from(&dense), maze);
+    /// ```
+    pub fn build_dense(self) -> Result<DenseMaze, MazeBuilderError> {
+        if self.weave.is_some() {
+            return Err(MazeBuilderError::GenerationError(
+                "weave crossings are not supported by dense mazes".to_string(),
+            ));
+        }
+        let direct = self.custom_generator.is_none()
+            && self.generator_type == GeneratorType::RecursiveBacktracking
+            && self.tiles.is_none()
+            && self.holes.is_empty()
+            && self.rooms.is_empty()
+            && self.loops.is_none()
+            && self.braid.is_none();
+        if !direct {
+            let mut maze = self.create_maze()?;
+            check_dense(maze.keys().copied(), maze.count())?;
+            if !maze.is_empty() {
+                let mut rng = create_rng(self.seed);
+                self.generate(&mut maze, &mut rng);
+            }
+            return Ok(DenseMaze::from(&maze));
+        }
+
+        // Generate on the positions of the shape, without building a `Maze` first
+        let positions = || self.shape.positions(self.radius, self.dimensions);
+        check_topology(positions()?, self.topology)?;
+        check_dense(positions()?, positions()?.count())?;
+        let mut dense = DenseMaze::from_positions(|| positions().into_iter().flatten());
+        dense.set_topology(self.topology);
+
+        let start = match self.start_position {
+            Some(start) if !dense.contains(&start) => {
+                return Err(MazeBuilderError::InvalidStartPosition(start));
+            }
+            Some(start) => start,
+            None => default_start(&dense, positions()?),
+        };
+        if !dense.is_empty() {
+            let mut rng = create_rng(self.seed);
+            let mut visited = dense.visited();
+            generate_backtracking(
+                &mut dense,
+                start,
+                self.bias,
+                &mut visited,
+                &mut rng,
+                &mut (),
+            );
+        }
+
+        Ok(dense)
+    }
+
+    /// Builds the hexagonal maze step by step, returning an iterator over the carved passages.
+    ///
+    /// The maze is fully generated before this returns, recording every carved passage, and the
+    /// iterator replays them one at a time. Draining it produces the same maze as
+    /// [`MazeBuilder::build`] with the same configuration and seed. See [`GenerationSteps`] for
+    /// inspecting the maze in between steps.
+    ///
+    /// Built-in generators report their passages in the order they carved them. Custom
+    /// generators that do not override [`MazeGenerator::generate_steps`] are replayed in tile
+    /// order instead, which does not show how they actually carved the maze.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`MazeBuilderError::NoRadius`] if no radius is specified for a radius-based shape.
+    /// Returns [`MazeBuilderError::NoDimensions`] if no dimensions are specified for a
+    /// rectangular shape.
+    /// Returns [`MazeBuilderError::DisconnectedTiles`] if the tiles set with
+    /// [`MazeBuilder::with_tiles`] are not all connected.
+    /// Returns [`MazeBuilderError::GenerationError`] if the holes set with
+    /// [`MazeBuilder::with_holes`] cut off tiles from the start position.
+    /// Returns [`MazeBuilderError::InvalidTopology`] if the topology set with
+    /// [`MazeBuilder::with_topology`] does not fit the tiles.
+    /// Returns [`MazeBuilderError::InvalidStartPosition`] if the start position is outside maze bounds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use hexlab::prelude::*;
+    ///
+    /// let steps = MazeBuilder::new()
+    ///     .with_radius(3)
+    ///     .build_steps()
+    ///     .expect("Failed to create maze");
+    ///
+    /// // A perfect maze carves one passage less than it has tiles
+    /// assert_eq!(steps.count(), 36);
+    /// ```
+    pub fn build_steps(self) -> Result<GenerationSteps, MazeBuilderError> {
+        let maze = self.create_maze()?;
+        let start = self.start(&maze);
+
+        let mut steps = StepLog::default();
+        if !maze.is_empty() {
+            let mut generated = maze.clone();
+            let mut rng = create_rng(self.seed);
+            if self.rooms.is_empty() {
+                let generator = self.generator();
+                for step in generator.generate_steps(&mut generated, start, &mut rng) {
+                    steps.record(step);
+                }
+            } else {
+                let generator = self.generator();
+                generate_with_rooms(
+                    &mut generated,
+                    &self.rooms,
+                    &*generator,
+                    start,
+                    &mut rng,
+                    &mut steps,
+                );
+            }
+            self.post_process(&mut generated, &mut rng, &mut steps);
+        }
+
+        Ok(GenerationSteps::new(maze, steps.into_steps(), start))
+    }
+
+    /// Creates the tiles of the maze with all walls present, validating the configuration.
+    fn create_maze(&self) -> Result<Maze, MazeBuilderError> {
+        let mut maze = match &self.tiles {
+            Some(tiles) => create_masked_maze(tiles)?,
+            None => self.shape.create_maze(self.radius, self.dimensions)?,
+        };
+        for hole in &self.holes {
+            maze.remove(hole);
+        }
+        check_topology(maze.keys().copied(), self.topology)?;
+        maze.set_topology(self.topology);
 
         if let Some(start_pos) = self.start_position {
             if maze.get(&start_pos).is_none() {
@@ -162,15 +775,129 @@ impl MazeBuilder {
             }
         }
 
-        if !maze.is_empty() {
-            self.generator_type
-                .generate(&mut maze, self.start_position, self.seed);
+        if !self.holes.is_empty() {
+            check_reachable(&maze, self.start(&maze))?;
         }
 
         Ok(maze)
     }
+
+    /// Returns the start position, falling back to the origin, or the lowest tile for shapes
+    /// that do not contain the origin.
+    fn start(&self, maze: &Maze) -> Hex {
+        self.start_position
+            .unwrap_or_else(|| default_start(maze, maze.keys().copied()))
+    }
+
+    /// Generates the maze in place with the configured generator, rooms and post-processing.
+    fn generate(&self, maze: &mut Maze, rng: &mut dyn RngCore) {
+        let start = self.start(maze);
+        if self.rooms.is_empty() {
+            self.generator().generate(maze, start, rng);
+        } else {
+            let generator = self.generator();
+            generate_with_rooms(maze, &self.rooms, &*generator, start, rng, &mut ());
+        }
+        self.post_process(maze, rng, &mut ());
+    }
+
+    /// Applies the configured post-processing to a generated maze.
+    fn post_process(&self, maze: &mut Maze, rng: &mut dyn RngCore, steps: &mut impl StepSink) {
+        if let Some(fraction) = self.weave {
+            weave(maze, fraction, rng, steps);
+        }
+        if let Some((count, min_length)) = self.loops {
+            add_loops(maze, count, min_length, rng, steps);
+        }
+        if let Some(fraction) = self.braid {
+            braid(maze, fraction, rng, steps);
+        }
+    }
+
+    /// Returns the generator used to generate the maze.
+    fn generator(&self) -> Box<dyn MazeGenerator + '_> {
+        self.custom_generator.as_deref().map_or_else(
+            || {
+                Box::new(Biased {
+                    generator: self.generator_type,
+                    bias: self.bias,
+                }) as Box<dyn MazeGenerator>
+            },
+            |generator| Box::new(generator),
+        )
+    }
+}
+
+/// Creates a maze of exactly `tiles`, checking that they are all connected.
+fn create_masked_maze(tiles: &[Hex]) -> Result<Maze, MazeBuilderError> {
+    let maze = tiles.iter().copied().collect::<Maze>();
+
+    let unique = sorted_tiles(maze.keys().copied());
+    let components = components(&maze, &unique, |_| true);
+    if components.len() > 1 {
+        let mut components = components.into_iter().map(sorted_tiles).collect::<Vec<_>>();
+        components.sort_by_key(|component| std::cmp::Reverse(component.len()));
+        return Err(MazeBuilderError::DisconnectedTiles(components));
+    }
+
+    Ok(maze)
+}
+
+/// Checks that `count` tiles at `positions` are close enough together to be stored in a
+/// [`DenseMaze`], without allocating its storage.
+fn check_dense(positions: impl Iterator<Item = Hex>, count: usize) -> Result<(), MazeBuilderError> {
+    if Bounds::around(positions).is_some_and(|bounds| bounds.fits(count)) {
+        return Ok(());
+    }
+    Err(MazeBuilderError::GenerationError(format!(
+        "{count} tiles are too far apart to be stored in a dense maze"
+    )))
 }
 
+/// Checks that the seams of `topology` line up and that it leaves every one of `tiles` in place.
+fn check_topology(
+    tiles: impl Iterator<Item = Hex>,
+    topology: Topology,
+) -> Result<(), MazeBuilderError> {
+    topology
+        .validate()
+        .map_err(MazeBuilderError::InvalidTopology)?;
+
+    let outside = sorted_tiles(tiles.filter(|&hex| topology.wrap(hex) != hex));
+    outside.first().map_or(Ok(()), |hex| {
+        Err(MazeBuilderError::InvalidTopology(format!(
+            "{} tiles from {hex:?} lie outside {topology:?}",
+            outside.len()
+        )))
+    })
+}
+
+/// Checks that every tile of `maze` can be reached from `start`.
+fn check_reachable(maze: &Maze, start: Hex) -> Result<(), MazeBuilderError> {
+    let tiles = sorted_tiles(maze.keys().copied());
+    let unreachable = components(maze, &tiles, |_| true)
+        .into_iter()
+        .filter(|component| !component.contains(&start))
+        .map(|component| {
+            let first = component.iter().min_by_key(|hex| (hex.x, hex.y));
+            format!(
+                "{} tiles from {:?}",
+                component.len(),
+                first.unwrap_or(&start)
+            )
+        })
+        .collect::<Vec<_>>();
+    if unreachable.is_empty() {
+        return Ok(());
+    }
+
+    Err(MazeBuilderError::GenerationError(format!(
+        "holes cut off {} from the start position {start:?}",
+        unreachable.join(", ")
+    )))
+}
+
+#[cfg(test)]
 pub fn create_hex_maze(radius: u16) -> Maze {
     let mut maze = Maze::new();
     let radius = i32::from(radius);
@@ -197,9 +924,21 @@ mod test {
     fn maze_builder_new() {
         let builder = MazeBuilder::new();
         assert_eq!(builder.radius, None);
+        assert_eq!(builder.dimensions, None);
+        assert_eq!(builder.shape, MazeShape::Hexagon);
+        assert_eq!(builder.tiles, None);
+        assert!(builder.holes.is_empty());
+        assert!(builder.rooms.is_empty());
+        assert_eq!(builder.topology, Topology::Bounded);
         assert_eq!(builder.seed, None);
         assert_eq!(builder.generator_type, GeneratorType::default());
+        assert_eq!(builder.bias, DirectionBias::Uniform);
         assert_eq!(builder.start_position, None);
+        assert!(builder.custom_generator.is_none());
+        assert!(builder.braid.is_none());
+        assert!(builder.loops.is_none());
+        assert!(builder.weave.is_none());
+        assert!(builder.levels.is_none());
     }
 
     #[rstest]
@@ -249,8 +988,8 @@ mod test {
             Hex::new(0, -2),
             Hex::new(1, -2),
         ];
-        for pos in expected_positions.iter() {
-            assert!(maze.get(pos).is_some(), "Expected tile at {:?}", pos);
+        for pos in &expected_positions {
+            assert!(maze.get(pos).is_some(), "Expected tile at {pos:?}");
         }
     }
 }
@@ -0,0 +1,525 @@
+use crate::{
+    errors::MazeError, generator::Visited, traits::MazeStorage, Maze, Tile, Topology, Walls,
+};
+#[cfg(feature = "bevy")]
+use bevy::prelude::*;
+use hexx::{EdgeDirection, Hex};
+
+/// The message of the panic when collecting tiles too far apart to index their positions.
+pub const TOO_FAR_APART: &str = "tiles are too far apart to be stored in a flat array";
+
+/// A hexagonal maze stored in a flat array, for large boards.
+///
+/// The walls of every tile are stored in a [`Vec`] indexed by the axial coordinates of the
+/// tile, relative to the bounding parallelogram of all tiles. Lookups are a bounds check and
+/// an index instead of a hash, and each tile takes two bytes instead of a full [`Tile`] and
+/// its key, which speeds up generating boards with millions of tiles.
+///
+/// Compact shapes such as hexagons and rectangles fill most of their bounding parallelogram,
+/// while sparse sets of tiles waste the space in between. [`MazeBuilder::build_dense`] refuses
+/// tiles spread over more than 64 times as many positions, while collecting or converting
+/// tiles too far apart to index panics. Weave crossings are not stored.
+///
+/// [`MazeBuilder::build_dense`]: crate::MazeBuilder::build_dense
+///
+/// Offers the same wall operations as [`Maze`], and converts from and into it.
+///
+/// # Examples
+///
+/// ```
+/// use hexlab::prelude::*;
+///
+/// let maze = MazeBuilder::new()
+///     .with_radius(50)
+///     .with_seed(12345)
+///     .build_dense()
+///     .expect("Failed to create maze");
+///
+/// assert_eq!(maze.count(), 7651);
+/// assert!(maze.get_walls(&Hex::ZERO).is_some());
+/// assert!(maze.get_walls(&Hex::new(51, 0)).is_none());
+/// ```
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "bevy_reflect", derive(bevy_reflect::Reflect))]
+#[cfg_attr(feature = "bevy", derive(Component))]
+#[cfg_attr(feature = "bevy", reflect(Component))]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DenseMaze {
+    bounds: Bounds,
+    walls: Vec<Option<Walls>>,
+    count: usize,
+    topology: Topology,
+}
+
+impl DenseMaze {
+    /// Creates a new empty maze.
+    #[inline]
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the number of tiles in the maze.
+    #[inline]
+    #[must_use]
+    pub const fn count(&self) -> usize {
+        self.count
+    }
+
+    /// Returns `true` if the maze contains no tiles.
+    #[inline]
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    /// Returns `true` if there is a tile at the specified coordinates.
+    #[inline]
+    #[must_use]
+    pub fn contains(&self, coord: &Hex) -> bool {
+        self.get_walls(coord).is_some()
+    }
+
+    /// Returns the tile at the specified coordinates.
+    ///
+    /// Unlike [`Maze::get`], the tile is assembled from its walls and returned by value.
+    #[must_use]
+    pub fn get(&self, coord: &Hex) -> Option<Tile> {
+        let walls = *self.get_walls(coord)?;
+        Some(Tile {
+            walls,
+            ..Tile::new(*coord)
+        })
+    }
+
+    /// Returns a reference to the walls of the tile at the specified coordinates.
+    #[inline]
+    #[must_use]
+    pub fn get_walls(&self, coord: &Hex) -> Option<&Walls> {
+        self.walls.get(self.bounds.index(*coord)?)?.as_ref()
+    }
+
+    /// Returns a mutable reference to the walls of the tile at the specified coordinates.
+    #[inline]
+    #[must_use]
+    pub fn get_walls_mut(&mut self, coord: &Hex) -> Option<&mut Walls> {
+        let index = self.bounds.index(*coord)?;
+        self.walls.get_mut(index)?.as_mut()
+    }
+
+    /// Adds a wall to a tile in the specified direction.
+    ///
+    /// Returns `true` if the wall was already present.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`MazeError::InvalidCoordinate`] if the specified coordinate does not exist in the maze.
+    pub fn add_tile_wall(
+        &mut self,
+        coord: &Hex,
+        direction: EdgeDirection,
+    ) -> Result<bool, MazeError> {
+        self.get_walls_mut(coord)
+            .map(|walls| walls.insert(direction))
+            .ok_or(MazeError::InvalidCoordinate(*coord))
+    }
+
+    /// Removes a wall from a tile in the specified direction.
+    ///
+    /// Returns `true` if the wall was present.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`MazeError::InvalidCoordinate`] if the specified coordinate does not exist in the maze.
+    pub fn remove_tile_wall(
+        &mut self,
+        coord: &Hex,
+        direction: EdgeDirection,
+    ) -> Result<bool, MazeError> {
+        self.get_walls_mut(coord)
+            .map(|walls| walls.remove(direction))
+            .ok_or(MazeError::InvalidCoordinate(*coord))
+    }
+
+    /// Removes the wall between a tile and its neighbor in the specified direction, on both
+    /// sides. See [`Maze::carve`].
+    ///
+    /// Returns `true` if the wall was present on either side.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`MazeError::InvalidCoordinate`] if the specified coordinate does not exist in the maze.
+    /// Returns [`MazeError::NoNeighbor`] if there is no tile in the specified direction.
+    pub fn carve(&mut self, coord: &Hex, direction: EdgeDirection) -> Result<bool, MazeError> {
+        let neighbor = self.shared_edge(*coord, direction)?;
+        let present = self.remove_tile_wall(coord, direction)?;
+        Ok(self.remove_tile_wall(&neighbor, direction.const_neg())? || present)
+    }
+
+    /// Adds the wall between a tile and its neighbor in the specified direction, on both
+    /// sides. See [`Maze::wall_up`].
+    ///
+    /// Returns `true` if the wall was already present on both sides.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`MazeError::InvalidCoordinate`] if the specified coordinate does not exist in the maze.
+    /// Returns [`MazeError::NoNeighbor`] if there is no tile in the specified direction.
+    pub fn wall_up(&mut self, coord: &Hex, direction: EdgeDirection) -> Result<bool, MazeError> {
+        let neighbor = self.shared_edge(*coord, direction)?;
+        let present = self.add_tile_wall(coord, direction)?;
+        Ok(self.add_tile_wall(&neighbor, direction.const_neg())? && present)
+    }
+
+    /// Returns the topology connecting the edges of the maze.
+    #[inline]
+    #[must_use]
+    pub const fn topology(&self) -> Topology {
+        self.topology
+    }
+
+    /// Sets the topology connecting the edges of the maze. See [`Maze::set_topology`].
+    #[inline]
+    pub const fn set_topology(&mut self, topology: Topology) {
+        self.topology = topology;
+    }
+
+    /// Returns the position of the tile next to `pos` in `direction`. See [`Maze::neighbor`].
+    #[must_use]
+    pub fn neighbor(&self, pos: Hex, direction: EdgeDirection) -> Option<Hex> {
+        let neighbor = self.topology.wrap(pos + direction);
+        (neighbor != pos && self.contains(&neighbor)).then_some(neighbor)
+    }
+
+    /// Returns an iterator over the tiles of the maze and their walls, sorted by row and
+    /// column.
+    pub fn iter(&self) -> impl Iterator<Item = (Hex, &Walls)> + '_ {
+        self.walls
+            .iter()
+            .enumerate()
+            .filter_map(|(index, walls)| Some((self.bounds.position(index), walls.as_ref()?)))
+    }
+
+    /// Creates a maze of the tiles at the positions returned by `positions`, with all walls
+    /// present.
+    ///
+    /// The positions are iterated twice, once for the bounds and once for the tiles, so they
+    /// are never collected.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the positions span more positions than can be indexed.
+    pub(crate) fn from_positions<I: Iterator<Item = Hex>>(positions: impl Fn() -> I) -> Self {
+        let bounds = Bounds::around(positions()).unwrap_or_else(|| panic!("{TOO_FAR_APART}"));
+        let mut maze = Self {
+            bounds,
+            walls: vec![None; bounds.len()],
+            count: 0,
+            topology: Topology::default(),
+        };
+        for hex in positions() {
+            if let Some(walls) = bounds
+                .index(hex)
+                .and_then(|index| maze.walls.get_mut(index))
+            {
+                maze.count += usize::from(walls.is_none());
+                *walls = Some(Walls::default());
+            }
+        }
+        maze
+    }
+
+    /// Returns an empty set of visited tiles, taking one bit per position in the bounds.
+    pub(crate) fn visited(&self) -> VisitedBits {
+        VisitedBits {
+            bounds: self.bounds,
+            bits: vec![0; self.bounds.len().div_ceil(64)],
+        }
+    }
+
+    /// Returns the neighbor sharing the edge of the tile at `coord` in `direction`.
+    fn shared_edge(&self, coord: Hex, direction: EdgeDirection) -> Result<Hex, MazeError> {
+        if !self.contains(&coord) {
+            return Err(MazeError::InvalidCoordinate(coord));
+        }
+        self.neighbor(coord, direction)
+            .ok_or(MazeError::NoNeighbor { coord, direction })
+    }
+}
+
+/// The bounding parallelogram of a set of tiles, mapping their axial coordinates to indices
+/// into a flat array, row by row.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "bevy_reflect", derive(bevy_reflect::Reflect))]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Bounds {
+    /// The corner with the lowest coordinates.
+    origin: Hex,
+    /// The number of columns along the `x` axis.
+    columns: u32,
+    /// The number of rows along the `y` axis.
+    rows: u32,
+}
+
+impl Bounds {
+    /// The most positions per tile the bounds may hold to store tiles densely, once they hold
+    /// more than [`Bounds::MIN_POSITIONS`].
+    ///
+    /// Well past the point where a [`DenseMaze`] saves memory over a [`Maze`], leaving room for
+    /// sparse shapes such as thin rings.
+    pub(crate) const MAX_POSITIONS_PER_TILE: usize = 64;
+    /// The positions the bounds may always hold, so small sets of tiles are never too sparse.
+    pub(crate) const MIN_POSITIONS: usize = 1 << 16;
+
+    /// Returns the bounds around `positions`, which are empty if there are none, or [`None`]
+    /// if they span more positions than can be indexed.
+    pub(crate) fn around(positions: impl Iterator<Item = Hex>) -> Option<Self> {
+        let extremes = positions.fold(None, |extremes, hex| {
+            let [min_x, max_x, min_y, max_y] = extremes.unwrap_or([hex.x, hex.x, hex.y, hex.y]);
+            Some([
+                min_x.min(hex.x),
+                max_x.max(hex.x),
+                min_y.min(hex.y),
+                max_y.max(hex.y),
+            ])
+        });
+        let Some([min_x, max_x, min_y, max_y]) = extremes else {
+            return Some(Self::default());
+        };
+        let span = |min: i32, max: i32| u32::try_from(i64::from(max) - i64::from(min) + 1).ok();
+        let bounds = Self {
+            origin: Hex::new(min_x, min_y),
+            columns: span(min_x, max_x)?,
+            rows: span(min_y, max_y)?,
+        };
+        usize::try_from(u64::from(bounds.columns) * u64::from(bounds.rows)).ok()?;
+        Some(bounds)
+    }
+
+    /// Returns `true` if the bounds are small enough to store `count` tiles densely, see
+    /// [`Bounds::MAX_POSITIONS_PER_TILE`].
+    pub(crate) fn fits(self, count: usize) -> bool {
+        self.len()
+            <= count
+                .saturating_mul(Self::MAX_POSITIONS_PER_TILE)
+                .max(Self::MIN_POSITIONS)
+    }
+
+    /// Returns the number of positions inside the bounds.
+    pub(crate) const fn len(self) -> usize {
+        self.columns as usize * self.rows as usize
+    }
+
+    /// Returns the index of `coord`, if it lies inside the bounds.
+    #[allow(clippy::cast_sign_loss)]
+    pub(crate) fn index(self, coord: Hex) -> Option<usize> {
+        let column = coord.x.checked_sub(self.origin.x)?;
+        let row = coord.y.checked_sub(self.origin.y)?;
+        if column < 0 || row < 0 || column as u32 >= self.columns || row as u32 >= self.rows {
+            return None;
+        }
+        Some(row as usize * self.columns as usize + column as usize)
+    }
+
+    /// Returns the position at `index`.
+    #[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
+    pub(crate) const fn position(self, index: usize) -> Hex {
+        let columns = self.columns as usize;
+        Hex::new(
+            self.origin.x + (index % columns) as i32,
+            self.origin.y + (index / columns) as i32,
+        )
+    }
+}
+
+/// The tiles visited while generating a [`DenseMaze`], as one bit per position in its bounds.
+pub struct VisitedBits {
+    bounds: Bounds,
+    bits: Vec<u64>,
+}
+
+impl Visited for VisitedBits {
+    fn visit(&mut self, pos: Hex) -> bool {
+        let Some(index) = self.bounds.index(pos) else {
+            return false;
+        };
+        let Some(word) = self.bits.get_mut(index / 64) else {
+            return false;
+        };
+        let bit = 1 << (index % 64);
+        let unvisited = *word & bit == 0;
+        *word |= bit;
+        unvisited
+    }
+}
+
+impl MazeStorage for DenseMaze {
+    fn count(&self) -> usize {
+        Self::count(self)
+    }
+
+    fn get_walls(&self, coord: &Hex) -> Option<&Walls> {
+        Self::get_walls(self, coord)
+    }
+
+    fn neighbor(&self, pos: Hex, direction: EdgeDirection) -> Option<Hex> {
+        Self::neighbor(self, pos, direction)
+    }
+
+    fn carve(&mut self, coord: &Hex, direction: EdgeDirection) -> Result<bool, MazeError> {
+        Self::carve(self, coord, direction)
+    }
+
+    fn wall_up(&mut self, coord: &Hex, direction: EdgeDirection) -> Result<bool, MazeError> {
+        Self::wall_up(self, coord, direction)
+    }
+}
+
+impl FromIterator<(Hex, Walls)> for DenseMaze {
+    fn from_iter<T: IntoIterator<Item = (Hex, Walls)>>(iter: T) -> Self {
+        let tiles = iter.into_iter().collect::<Vec<_>>();
+        let bounds = Bounds::around(tiles.iter().map(|&(hex, _)| hex))
+            .unwrap_or_else(|| panic!("{TOO_FAR_APART}"));
+        let mut maze = Self {
+            bounds,
+            walls: vec![None; bounds.len()],
+            count: 0,
+            topology: Topology::default(),
+        };
+        for (hex, walls) in tiles {
+            if let Some(index) = bounds.index(hex) {
+                maze.count += usize::from(maze.walls[index].is_none());
+                maze.walls[index] = Some(walls);
+            }
+        }
+        maze
+    }
+}
+
+impl FromIterator<Hex> for DenseMaze {
+    fn from_iter<T: IntoIterator<Item = Hex>>(iter: T) -> Self {
+        iter.into_iter()
+            .map(|hex| (hex, Walls::default()))
+            .collect()
+    }
+}
+
+impl From<&Maze> for DenseMaze {
+    /// Converts the tiles, walls and topology of a maze.
+    ///
+    /// Weave crossings are lost. The walls of every tile are copied as they are, so a tunnel
+    /// is left open on the side of the tiles it joined and walled on the side of the tile it
+    /// passed under. Check [`Tile::under_passage`] first if the maze may have been woven.
+    fn from(maze: &Maze) -> Self {
+        let mut dense = maze
+            .iter()
+            .map(|(&hex, tile)| (hex, tile.walls))
+            .collect::<Self>();
+        dense.topology = maze.topology();
+        dense
+    }
+}
+
+impl From<&DenseMaze> for Maze {
+    fn from(dense: &DenseMaze) -> Self {
+        let mut maze = dense
+            .iter()
+            .map(|(hex, &walls)| Tile {
+                walls,
+                ..Tile::new(hex)
+            })
+            .collect::<Self>();
+        maze.set_topology(dense.topology);
+        maze
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::builder::create_hex_maze;
+
+    #[test]
+    fn dense_maze_matches_maze() {
+        let maze = create_hex_maze(3);
+        let dense = DenseMaze::from(&maze);
+
+        assert_eq!(dense.count(), maze.count());
+        for (&hex, tile) in maze.iter() {
+            assert_eq!(dense.get_walls(&hex), Some(&tile.walls));
+            assert_eq!(dense.get(&hex).as_ref(), Some(tile));
+        }
+        assert_eq!(Maze::from(&dense), maze);
+    }
+
+    #[test]
+    fn positions_outside_bounds() {
+        let dense = Hex::ZERO.range(2).collect::<DenseMaze>();
+
+        // Inside the bounding parallelogram, but not a tile of the hexagon
+        assert!(!dense.contains(&Hex::new(2, 2)));
+        assert!(!dense.contains(&Hex::new(-3, 0)));
+        assert!(!dense.contains(&Hex::new(i32::MIN, i32::MAX)));
+        assert!(DenseMaze::new().get_walls(&Hex::ZERO).is_none());
+    }
+
+    #[test]
+    fn bounds_too_far_apart() {
+        let far = [Hex::new(i32::MIN, 0), Hex::new(i32::MAX, 0)];
+        assert_eq!(Bounds::around(far.into_iter()), None);
+
+        let sparse = Bounds::around([Hex::ZERO, Hex::new(1000, 1000)].into_iter());
+        assert!(sparse.is_some_and(|bounds| !bounds.fits(2)));
+        assert!(sparse.is_some_and(|bounds| bounds.fits(1000 * 1000)));
+        assert_eq!(Bounds::around(std::iter::empty()), Some(Bounds::default()));
+    }
+
+    #[test]
+    fn iter_round_trips() {
+        let dense = Hex::new(4, -7).range(3).collect::<DenseMaze>();
+        let positions = dense.iter().map(|(hex, _)| hex).collect::<Vec<_>>();
+
+        assert_eq!(positions.len(), dense.count());
+        assert!(positions.iter().all(|hex| dense.contains(hex)));
+    }
+
+    #[test]
+    fn from_positions_matches_collect() {
+        let dense = DenseMaze::from_positions(|| Hex::new(4, -7).range(3));
+        assert_eq!(dense, Hex::new(4, -7).range(3).collect::<DenseMaze>());
+    }
+
+    #[test]
+    fn visited_bits() {
+        let dense = Hex::ZERO.range(5).collect::<DenseMaze>();
+        let mut visited = dense.visited();
+
+        for hex in Hex::ZERO.range(5) {
+            assert!(visited.visit(hex));
+            assert!(!visited.visit(hex));
+        }
+        assert!(!visited.visit(Hex::new(100, 0)));
+    }
+
+    #[test]
+    fn carve_keeps_both_sides_in_sync() {
+        let mut dense = Hex::ZERO.range(1).collect::<DenseMaze>();
+        let direction = EdgeDirection::FLAT_NORTH;
+
+        assert_eq!(dense.carve(&Hex::ZERO, direction), Ok(true));
+        assert_eq!(
+            dense
+                .get_walls(&(Hex::ZERO + direction))
+                .map(|walls| walls.contains(-direction)),
+            Some(false)
+        );
+        assert_eq!(dense.wall_up(&Hex::ZERO, direction), Ok(false));
+        assert_eq!(
+            dense.carve(&(Hex::ZERO + direction), direction),
+            Err(MazeError::NoNeighbor {
+                coord: Hex::ZERO + direction,
+                direction
+            })
+        );
+    }
+}
@@ -0,0 +1,372 @@
+use crate::{
+    dense::{Bounds, TOO_FAR_APART},
+    errors::MazeError,
+    Maze, Tile, Topology, Walls,
+};
+#[cfg(feature = "bevy")]
+use bevy::prelude::*;
+use hexx::{EdgeDirection, Hex};
+
+/// Marks a position of the bounds as holding a tile, next to the bits of its owned edges.
+const PRESENT: u8 = 1 << 3;
+
+/// A hexagonal maze storing every wall once, on the edge shared by two tiles.
+///
+/// Each tile owns the three edges towards [`EdgeDirection::FLAT_SOUTH_EAST`],
+/// [`EdgeDirection::FLAT_SOUTH`] and [`EdgeDirection::FLAT_SOUTH_WEST`], and the other three
+/// belong to its neighbors. As both sides of a wall are the same bit, walls can never be
+/// asymmetric, and each tile takes a single byte, half of what [`DenseMaze`] needs. Edges
+/// facing a position without a tile are always walled.
+///
+/// Tiles are stored in a flat array like [`DenseMaze`], and collecting or converting tiles too
+/// far apart to index panics. Weave crossings are not stored, and converting a woven [`Maze`]
+/// walls up its tunnels.
+///
+/// [`DenseMaze`]: crate::DenseMaze
+///
+/// # Examples
+///
+/// ```
+/// use hexlab::prelude::*;
+///
+/// let maze = MazeBuilder::new()
+///     .with_radius(3)
+///     .with_seed(12345)
+///     .build()
+///     .expect("Failed to create maze");
+///
+/// let edges = EdgeMaze::from(&maze);
+/// assert_eq!(edges.count(), 37);
+/// assert_eq!(edges.get_walls(&Hex::ZERO), maze.get_walls(&Hex::ZERO).copied());
+/// assert_eq!(Maze::from(&edges), maze);
+/// ```
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "bevy_reflect", derive(bevy_reflect::Reflect))]
+#[cfg_attr(feature = "bevy", derive(Component))]
+#[cfg_attr(feature = "bevy", reflect(Component))]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EdgeMaze {
+    bounds: Bounds,
+    /// The owned edges of every position, with [`PRESENT`] set if there is a tile.
+    edges: Vec<u8>,
+    count: usize,
+    topology: Topology,
+}
+
+impl EdgeMaze {
+    /// Creates a new empty maze.
+    #[inline]
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the number of tiles in the maze.
+    #[inline]
+    #[must_use]
+    pub const fn count(&self) -> usize {
+        self.count
+    }
+
+    /// Returns `true` if the maze contains no tiles.
+    #[inline]
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    /// Returns `true` if there is a tile at the specified coordinates.
+    #[inline]
+    #[must_use]
+    pub fn contains(&self, coord: &Hex) -> bool {
+        self.slot(*coord).is_some()
+    }
+
+    /// Returns the walls of the tile at the specified coordinates, gathered from the edges
+    /// it shares with its neighbors.
+    #[must_use]
+    pub fn get_walls(&self, coord: &Hex) -> Option<Walls> {
+        self.slot(*coord)?;
+        Some(
+            EdgeDirection::ALL_DIRECTIONS
+                .into_iter()
+                .filter(|&direction| self.has_wall(*coord, direction))
+                .collect(),
+        )
+    }
+
+    /// Returns the tile at the specified coordinates, assembled from its walls.
+    #[must_use]
+    pub fn get(&self, coord: &Hex) -> Option<Tile> {
+        let walls = self.get_walls(coord)?;
+        Some(Tile {
+            walls,
+            ..Tile::new(*coord)
+        })
+    }
+
+    /// Removes the wall between a tile and its neighbor in the specified direction.
+    ///
+    /// Returns `true` if the wall was present.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`MazeError::InvalidCoordinate`] if the specified coordinate does not exist in the maze.
+    /// Returns [`MazeError::NoNeighbor`] if there is no tile in the specified direction.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use hexlab::prelude::*;
+    ///
+    /// let neighbor = Hex::ZERO + EdgeDirection::FLAT_NORTH;
+    /// let mut maze = EdgeMaze::from_iter([Hex::ZERO, neighbor]);
+    ///
+    /// assert_eq!(maze.carve(&Hex::ZERO, EdgeDirection::FLAT_NORTH), Ok(true));
+    /// assert!(!maze.get_walls(&neighbor).unwrap().contains(EdgeDirection::FLAT_SOUTH));
+    /// ```
+    pub fn carve(&mut self, coord: &Hex, direction: EdgeDirection) -> Result<bool, MazeError> {
+        let (index, bit) = self.edge(*coord, direction)?;
+        let present = self.edges[index] & bit != 0;
+        self.edges[index] &= !bit;
+        Ok(present)
+    }
+
+    /// Adds the wall between a tile and its neighbor in the specified direction.
+    ///
+    /// Returns `true` if the wall was already present.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`MazeError::InvalidCoordinate`] if the specified coordinate does not exist in the maze.
+    /// Returns [`MazeError::NoNeighbor`] if there is no tile in the specified direction.
+    pub fn wall_up(&mut self, coord: &Hex, direction: EdgeDirection) -> Result<bool, MazeError> {
+        let (index, bit) = self.edge(*coord, direction)?;
+        let present = self.edges[index] & bit != 0;
+        self.edges[index] |= bit;
+        Ok(present)
+    }
+
+    /// Returns the topology connecting the edges of the maze.
+    #[inline]
+    #[must_use]
+    pub const fn topology(&self) -> Topology {
+        self.topology
+    }
+
+    /// Sets the topology connecting the edges of the maze. See [`Maze::set_topology`].
+    #[inline]
+    pub const fn set_topology(&mut self, topology: Topology) {
+        self.topology = topology;
+    }
+
+    /// Returns the position of the tile next to `pos` in `direction`. See [`Maze::neighbor`].
+    #[must_use]
+    pub fn neighbor(&self, pos: Hex, direction: EdgeDirection) -> Option<Hex> {
+        let neighbor = self.topology.wrap(pos + direction);
+        (neighbor != pos && self.contains(&neighbor)).then_some(neighbor)
+    }
+
+    /// Returns an iterator over the tiles of the maze and their walls, sorted by row and
+    /// column.
+    pub fn iter(&self) -> impl Iterator<Item = (Hex, Walls)> + '_ {
+        (0..self.edges.len())
+            .filter(|&index| self.edges[index] & PRESENT != 0)
+            .map(|index| {
+                let hex = self.bounds.position(index);
+                let walls = EdgeDirection::ALL_DIRECTIONS
+                    .into_iter()
+                    .filter(|&direction| self.has_wall(hex, direction))
+                    .collect();
+                (hex, walls)
+            })
+    }
+
+    /// Walls up every edge of the tile at `coord` with a wall in `walls`.
+    fn wall_up_all(&mut self, coord: Hex, walls: Walls) {
+        for direction in EdgeDirection::ALL_DIRECTIONS {
+            if walls.contains(direction) {
+                let _ = self.wall_up(&coord, direction);
+            }
+        }
+    }
+
+    /// Returns the index of the tile at `coord`, if there is one.
+    fn slot(&self, coord: Hex) -> Option<usize> {
+        let index = self.bounds.index(coord)?;
+        (self.edges[index] & PRESENT != 0).then_some(index)
+    }
+
+    /// Returns `true` if there is a wall on the edge of the tile at `coord` in `direction`.
+    fn has_wall(&self, coord: Hex, direction: EdgeDirection) -> bool {
+        self.edge(coord, direction)
+            .map_or(true, |(index, bit)| self.edges[index] & bit != 0)
+    }
+
+    /// Returns the index of the tile owning the edge of the tile at `coord` in `direction`,
+    /// and the bit of the edge.
+    fn edge(&self, coord: Hex, direction: EdgeDirection) -> Result<(usize, u8), MazeError> {
+        let index = self
+            .slot(coord)
+            .ok_or(MazeError::InvalidCoordinate(coord))?;
+        let neighbor = self
+            .neighbor(coord, direction)
+            .ok_or(MazeError::NoNeighbor { coord, direction })?;
+        if owns(direction) {
+            return Ok((index, 1 << direction.index()));
+        }
+        let owner = self
+            .slot(neighbor)
+            .ok_or(MazeError::NoNeighbor { coord, direction })?;
+        Ok((owner, 1 << direction.const_neg().index()))
+    }
+}
+
+/// Returns `true` if a tile owns its edge in `direction`.
+const fn owns(direction: EdgeDirection) -> bool {
+    direction.index() < 3
+}
+
+impl FromIterator<(Hex, Walls)> for EdgeMaze {
+    /// Collects tiles with their walls, keeping a wall present on either side of an edge.
+    fn from_iter<T: IntoIterator<Item = (Hex, Walls)>>(iter: T) -> Self {
+        let tiles = iter.into_iter().collect::<Vec<_>>();
+        let bounds = Bounds::around(tiles.iter().map(|&(hex, _)| hex))
+            .unwrap_or_else(|| panic!("{TOO_FAR_APART}"));
+        let mut maze = Self {
+            bounds,
+            edges: vec![0; bounds.len()],
+            count: 0,
+            topology: Topology::default(),
+        };
+        for &(hex, _) in &tiles {
+            if let Some(index) = bounds.index(hex) {
+                maze.count += usize::from(maze.edges[index] & PRESENT == 0);
+                maze.edges[index] = PRESENT;
+            }
+        }
+        for (hex, walls) in tiles {
+            maze.wall_up_all(hex, walls);
+        }
+        maze
+    }
+}
+
+impl FromIterator<Hex> for EdgeMaze {
+    fn from_iter<T: IntoIterator<Item = Hex>>(iter: T) -> Self {
+        iter.into_iter()
+            .map(|hex| (hex, Walls::default()))
+            .collect()
+    }
+}
+
+impl From<&Maze> for EdgeMaze {
+    /// Converts the tiles, walls and topology of a maze.
+    ///
+    /// Weave crossings are lost. A tunnel is open on the side of the tiles it joins but walled
+    /// on the side of the tile it passes under, and as a wall present on either side is kept,
+    /// every tunnel becomes a wall. Check [`Tile::under_passage`] first if the maze may have
+    /// been woven.
+    fn from(maze: &Maze) -> Self {
+        let mut edges = Self {
+            topology: maze.topology(),
+            ..maze.keys().map(|&hex| (hex, Walls::empty())).collect()
+        };
+        for (&hex, tile) in maze.iter() {
+            edges.wall_up_all(hex, tile.walls);
+        }
+        edges
+    }
+}
+
+impl From<&EdgeMaze> for Maze {
+    fn from(edges: &EdgeMaze) -> Self {
+        let mut maze = edges
+            .iter()
+            .map(|(hex, walls)| Tile {
+                walls,
+                ..Tile::new(hex)
+            })
+            .collect::<Self>();
+        maze.set_topology(edges.topology);
+        maze
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{builder::create_hex_maze, GenerationStep};
+
+    #[test]
+    fn walls_are_shared() {
+        let mut maze = Hex::ZERO.range(1).collect::<EdgeMaze>();
+        let direction = EdgeDirection::FLAT_NORTH;
+        let neighbor = Hex::ZERO + direction;
+
+        // Carving from either side opens the same edge
+        assert_eq!(maze.carve(&neighbor, -direction), Ok(true));
+        assert_eq!(maze.carve(&Hex::ZERO, direction), Ok(false));
+        assert_eq!(
+            maze.get_walls(&Hex::ZERO)
+                .map(|walls| walls.contains(direction)),
+            Some(false)
+        );
+        assert_eq!(maze.wall_up(&Hex::ZERO, direction), Ok(false));
+        assert_eq!(maze.wall_up(&neighbor, -direction), Ok(true));
+    }
+
+    #[test]
+    fn outer_edges_stay_walled() {
+        let mut maze = Hex::ZERO.range(1).collect::<EdgeMaze>();
+        let edge = Hex::new(0, -1);
+
+        assert_eq!(
+            maze.carve(&edge, EdgeDirection::FLAT_NORTH),
+            Err(MazeError::NoNeighbor {
+                coord: edge,
+                direction: EdgeDirection::FLAT_NORTH
+            })
+        );
+        assert_eq!(maze.get_walls(&edge), Some(Walls::default()));
+        assert_eq!(
+            maze.carve(&Hex::new(3, 0), EdgeDirection::FLAT_NORTH),
+            Err(MazeError::InvalidCoordinate(Hex::new(3, 0)))
+        );
+    }
+
+    #[test]
+    fn asymmetric_walls_are_closed() {
+        let mut maze = create_hex_maze(2);
+        let _ = maze.remove_tile_wall(&Hex::ZERO, EdgeDirection::FLAT_NORTH);
+        let _ = maze.remove_tile_wall(&Hex::ZERO, EdgeDirection::FLAT_SOUTH);
+        let _ = maze.carve(&Hex::ZERO, EdgeDirection::FLAT_SOUTH_EAST);
+
+        // Only the passage open on both sides is kept
+        let mut expected = Walls::default();
+        expected.remove(EdgeDirection::FLAT_SOUTH_EAST);
+        assert_eq!(EdgeMaze::from(&maze).get_walls(&Hex::ZERO), Some(expected));
+    }
+
+    #[test]
+    fn wrapping_edges_are_shared() {
+        let mut maze = create_hex_maze(2);
+        maze.set_topology(Topology::Hexagon { radius: 2 });
+        let _ = maze.carve(&Hex::new(0, -2), EdgeDirection::FLAT_NORTH);
+
+        let edges = EdgeMaze::from(&maze);
+        assert_eq!(Maze::from(&edges), maze);
+    }
+
+    #[test]
+    fn tunnels_are_walled_up() {
+        let (west, east) = (Hex::new(-1, 0), Hex::new(1, 0));
+        let direction = EdgeDirection::FLAT_SOUTH_EAST;
+        let mut maze = Maze::from([west, Hex::ZERO, east]);
+        GenerationStep::tunnel(west, Hex::ZERO, east, direction).apply(&mut maze);
+
+        let edges = EdgeMaze::from(&maze);
+        assert_eq!(edges.get_walls(&west), Some(Walls::default()));
+        assert_eq!(edges.get_walls(&east), Some(Walls::default()));
+    }
+}
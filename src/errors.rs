@@ -9,6 +9,20 @@ pub enum MazeBuilderError {
     #[error("Radius must be specified to build a maze")]
     NoRadius,
 
+    /// Occurs when attempting to build a rectangular maze without specifying its dimensions.
+    #[error("Dimensions must be specified to build a maze of this shape")]
+    NoDimensions,
+
+    /// Occurs when the tiles set with `MazeBuilder::with_tiles` are not all connected.
+    ///
+    /// Lists the tiles of every connected component, largest first.
+    #[error("Tiles form {count} disconnected components: {0:?}", count = .0.len())]
+    DisconnectedTiles(Vec<Vec<Hex>>),
+
+    /// Occurs when the topology set with `MazeBuilder::with_topology` does not fit the tiles.
+    #[error("Invalid topology: {0}")]
+    InvalidTopology(String),
+
     /// Occurs when the specified start position is outside the maze bounds.
     #[error("Start position {0:?} is outside maze bounds")]
     InvalidStartPosition(Hex),
@@ -24,6 +38,17 @@ pub enum MazeError {
     #[error("Invalid coordinate: {0:?}")]
     InvalidCoordinate(Hex),
 
+    /// Error when attempting to access a level that does not exist in a layered maze.
+    #[error("Invalid level: {0}")]
+    InvalidLevel(usize),
+
+    /// Error when a wall operation on both sides of an edge has no tile on the other side.
+    #[error("No neighbor of {coord:?} in direction {direction:?}")]
+    NoNeighbor {
+        coord: Hex,
+        direction: EdgeDirection,
+    },
+
     /// Error when a tile's internal position doesn't match its insertion coordinate.
     #[error("Tile position ({tile_pos:?}) does not match insertion coordinates ({insert_pos:?})")]
     PositionMismatch { tile_pos: Hex, insert_pos: Hex },
@@ -0,0 +1,100 @@
+use super::{
+    carve, reachable_tiles,
+    steps::StepSink,
+    wilson::{loop_erased_walks, random_neighbor_direction},
+};
+use crate::Maze;
+use hexx::Hex;
+use rand::Rng;
+use std::collections::HashSet;
+
+/// Random walks from `start`, carving into every tile visited for the first time.
+///
+/// Once `switch_at` percent of the tiles are visited, the remaining tiles are added with
+/// Wilson's algorithm. Both algorithms produce uniform spanning trees, so the result is
+/// uniform regardless of the percentage.
+pub(super) fn aldous_broder<R: Rng + ?Sized>(
+    maze: &mut Maze,
+    start: Hex,
+    switch_at: u8,
+    rng: &mut R,
+    steps: &mut impl StepSink,
+) {
+    let tiles = reachable_tiles(maze, start);
+    let target = (usize::from(switch_at.min(100)) * tiles.len()).div_ceil(100);
+    let mut visited = HashSet::from([start]);
+
+    let mut current = start;
+    while visited.len() < target {
+        let Some(direction) = random_neighbor_direction(maze, current, rng) else {
+            return;
+        };
+        let Some(neighbor) = maze.neighbor(current, direction) else {
+            return;
+        };
+        if visited.insert(neighbor) {
+            carve(maze, current, direction, steps);
+        }
+        current = neighbor;
+    }
+
+    loop_erased_walks(maze, &tiles, &mut visited, rng, steps);
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        builder::create_hex_maze,
+        generator::{connected_tiles, open_passages},
+    };
+    use rand::{rngs::StdRng, SeedableRng};
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(Hex::ZERO, 100)]
+    #[case(Hex::new(1, -1), 100)]
+    #[case(Hex::ZERO, 50)]
+    #[case(Hex::new(-2, 2), 0)]
+    #[case(Hex::ZERO, u8::MAX)]
+    fn aldous_broder_spanning_tree(#[case] start: Hex, #[case] switch_at: u8) {
+        let mut maze = create_hex_maze(4);
+        let mut rng = StdRng::seed_from_u64(12345);
+
+        aldous_broder(&mut maze, start, switch_at, &mut rng, &mut ());
+
+        assert_eq!(
+            connected_tiles(&maze, start).len(),
+            maze.count(),
+            "All tiles should be reachable"
+        );
+        assert_eq!(
+            open_passages(&maze),
+            maze.count() - 1,
+            "Maze should be a spanning tree"
+        );
+    }
+
+    #[test]
+    fn aldous_broder_deterministic() {
+        let mut maze1 = create_hex_maze(5);
+        let mut maze2 = create_hex_maze(5);
+
+        aldous_broder(
+            &mut maze1,
+            Hex::ZERO,
+            50,
+            &mut StdRng::seed_from_u64(12345),
+            &mut (),
+        );
+        aldous_broder(
+            &mut maze2,
+            Hex::ZERO,
+            50,
+            &mut StdRng::seed_from_u64(12345),
+            &mut (),
+        );
+
+        assert_eq!(maze1, maze2, "Same seed should produce identical mazes");
+    }
+}
@@ -1,41 +1,59 @@
-use crate::Maze;
-use hexx::{EdgeDirection, Hex};
-use rand::{rngs::StdRng, seq::SliceRandom, thread_rng, Rng, RngCore, SeedableRng};
+use super::{carve, steps::StepSink};
+use crate::{DirectionBias, MazeStorage};
+use hexx::Hex;
+use rand::{Rng, RngCore};
 use std::collections::HashSet;
 
-pub(super) fn generate_backtracking(maze: &mut Maze, start_pos: Option<Hex>, seed: Option<u64>) {
-    if maze.is_empty() {
-        return;
-    }
-
-    let start = start_pos.unwrap_or(Hex::ZERO);
-
-    let mut visited = HashSet::new();
+/// The set of tiles visited so far during generation.
+pub trait Visited {
+    /// Marks the tile at `pos` as visited, returning `true` if it was not visited before.
+    fn visit(&mut self, pos: Hex) -> bool;
+}
 
-    let mut rng: Box<dyn RngCore> = seed.map_or_else(
-        || Box::new(thread_rng()) as Box<dyn RngCore>,
-        |seed| Box::new(StdRng::seed_from_u64(seed)) as Box<dyn RngCore>,
-    );
+impl Visited for HashSet<Hex> {
+    fn visit(&mut self, pos: Hex) -> bool {
+        self.insert(pos)
+    }
+}
 
-    recursive_backtrack(maze, start, &mut visited, &mut rng);
+pub fn generate_backtracking<M: MazeStorage>(
+    maze: &mut M,
+    start: Hex,
+    bias: DirectionBias,
+    visited: &mut impl Visited,
+    rng: &mut dyn RngCore,
+    steps: &mut impl StepSink,
+) {
+    recursive_backtrack(maze, start, bias, visited, rng, steps);
 }
 
-fn recursive_backtrack<R: Rng>(
-    maze: &mut Maze,
-    current: Hex,
-    visited: &mut HashSet<Hex>,
+fn recursive_backtrack<M: MazeStorage, R: Rng + ?Sized>(
+    maze: &mut M,
+    start: Hex,
+    bias: DirectionBias,
+    visited: &mut impl Visited,
     rng: &mut R,
+    steps: &mut impl StepSink,
 ) {
-    visited.insert(current);
-    let mut directions = EdgeDirection::ALL_DIRECTIONS;
-    directions.shuffle(rng);
-
-    for direction in directions {
-        let neighbor = current + direction;
-        if maze.get(&neighbor).is_some() && !visited.contains(&neighbor) {
-            let _ = maze.remove_tile_wall(&current, direction);
-            let _ = maze.remove_tile_wall(&neighbor, direction.const_neg());
-            recursive_backtrack(maze, neighbor, visited, rng);
+    // Each frame holds a tile with its shuffled directions and the index of the next
+    // direction to try, mirroring a recursive call without growing the call stack.
+    let mut stack = vec![(start, bias.shuffled(None, rng), 0)];
+    visited.visit(start);
+
+    while let Some((current, directions, next)) = stack.last_mut() {
+        let Some(&direction) = directions.get(*next) else {
+            stack.pop();
+            continue;
+        };
+        *next += 1;
+
+        let current = *current;
+        let Some(neighbor) = maze.neighbor(current, direction) else {
+            continue;
+        };
+        if visited.visit(neighbor) {
+            carve(maze, current, direction, steps);
+            stack.push((neighbor, bias.shuffled(Some(direction), rng), 0));
         }
     }
 }
@@ -43,7 +61,8 @@ fn recursive_backtrack<R: Rng>(
 #[cfg(test)]
 mod test {
     use super::*;
-    use crate::builder::create_hex_maze;
+    use crate::{builder::create_hex_maze, generator::connected_tiles};
+    use rand::{rngs::StdRng, SeedableRng};
     use rstest::rstest;
 
     #[rstest]
@@ -55,7 +74,14 @@ mod test {
         let mut rng = StdRng::seed_from_u64(12345);
         let mut visited = HashSet::new();
 
-        recursive_backtrack(&mut maze, start, &mut visited, &mut rng);
+        recursive_backtrack(
+            &mut maze,
+            start,
+            DirectionBias::Uniform,
+            &mut visited,
+            &mut rng,
+            &mut (),
+        );
 
         assert!(visited.contains(&start), "Start position should be visited");
     }
@@ -69,7 +95,14 @@ mod test {
         let mut rng = StdRng::seed_from_u64(12345);
         let mut visited = HashSet::new();
 
-        recursive_backtrack(&mut maze, start, &mut visited, &mut rng);
+        recursive_backtrack(
+            &mut maze,
+            start,
+            DirectionBias::Uniform,
+            &mut visited,
+            &mut rng,
+            &mut (),
+        );
 
         for &pos in maze.keys() {
             let walls = maze.get_walls(&pos).unwrap();
@@ -89,27 +122,38 @@ mod test {
         let mut rng = StdRng::seed_from_u64(12345);
         let mut visited = HashSet::new();
 
-        recursive_backtrack(&mut maze, start, &mut visited, &mut rng);
-
-        let mut to_visit = vec![start];
-        let mut connected = HashSet::new();
-        while let Some(current) = to_visit.pop() {
-            if !connected.insert(current) {
-                continue;
-            }
-            for dir in EdgeDirection::ALL_DIRECTIONS {
-                let neighbor = current + dir;
-                if let Some(walls) = maze.get_walls(&current) {
-                    if !walls.contains(dir) && maze.get(&neighbor).is_some() {
-                        to_visit.push(neighbor);
-                    }
-                }
-            }
-        }
+        recursive_backtrack(
+            &mut maze,
+            start,
+            DirectionBias::Uniform,
+            &mut visited,
+            &mut rng,
+            &mut (),
+        );
+
+        let connected = connected_tiles(&maze, start);
         assert_eq!(
             connected.len(),
             maze.count(),
             "All tiles should be connected"
         );
     }
+
+    #[test]
+    fn recursive_backtrack_large_radius() {
+        let mut maze = create_hex_maze(500);
+        let mut rng = StdRng::seed_from_u64(12345);
+        let mut visited = HashSet::new();
+
+        recursive_backtrack(
+            &mut maze,
+            Hex::ZERO,
+            DirectionBias::Uniform,
+            &mut visited,
+            &mut rng,
+            &mut (),
+        );
+
+        assert_eq!(visited.len(), maze.count(), "All tiles should be visited");
+    }
 }
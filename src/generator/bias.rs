@@ -0,0 +1,152 @@
+use hexx::EdgeDirection;
+use rand::{seq::SliceRandom, Rng};
+
+/// Weights the random order in which generators try the directions out of a tile.
+///
+/// Used by recursive backtracking, growing tree, hunt-and-kill and Prim's algorithm, and
+/// ignored by the other generators. A weight of `1.0` leaves a direction as likely as any
+/// other, larger weights make it more likely and smaller ones less likely.
+///
+/// # Examples
+///
+/// ```
+/// use hexlab::prelude::*;
+///
+/// // Corridors flowing sideways, for a side-scrolling view
+/// let maze = MazeBuilder::new()
+///     .with_radius(5)
+///     .with_bias(DirectionBias::Axis {
+///         direction: EdgeDirection::POINTY_EAST,
+///         weight: 4.0,
+///     })
+///     .build()
+///     .expect("Failed to create maze");
+///
+/// assert_eq!(maze.count(), 91);
+/// ```
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "bevy_reflect", derive(bevy_reflect::Reflect))]
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum DirectionBias {
+    /// Every direction is equally likely.
+    #[default]
+    Uniform,
+    /// Continuing in the direction a tile was entered from is `weight` times as likely as
+    /// turning, producing long straight corridors for weights above `1.0`.
+    Straight { weight: f32 },
+    /// Moving along the axis of `direction`, either way, is `weight` times as likely as
+    /// moving along the other two axes.
+    Axis {
+        direction: EdgeDirection,
+        weight: f32,
+    },
+}
+
+impl DirectionBias {
+    /// Returns the weight of moving in `direction` out of a tile entered in `previous`.
+    pub(super) fn weight(self, direction: EdgeDirection, previous: Option<EdgeDirection>) -> f32 {
+        let weight = match self {
+            Self::Straight { weight } if previous == Some(direction) => weight,
+            Self::Axis {
+                direction: axis,
+                weight,
+            } if direction == axis || direction == axis.const_neg() => weight,
+            Self::Uniform | Self::Straight { .. } | Self::Axis { .. } => 1.0,
+        };
+        weight.max(0.0)
+    }
+
+    /// Shuffles `directions` out of a tile entered in `previous`, placing each direction
+    /// earlier the larger its weight.
+    ///
+    /// Without a bias this is a plain shuffle, so unbiased mazes are unaffected.
+    pub(super) fn shuffle<R: Rng + ?Sized>(
+        self,
+        directions: &mut [EdgeDirection],
+        previous: Option<EdgeDirection>,
+        rng: &mut R,
+    ) {
+        if self == Self::Uniform {
+            directions.shuffle(rng);
+            return;
+        }
+
+        // Weighted random permutation, ordering by `u^(1 / weight)` for a uniform `u`
+        let mut keyed = directions
+            .iter()
+            .map(|&direction| {
+                let key = rng
+                    .gen::<f32>()
+                    .powf(self.weight(direction, previous).recip());
+                (key, direction)
+            })
+            .collect::<Vec<_>>();
+        keyed.sort_by(|a, b| b.0.total_cmp(&a.0));
+        for (slot, (_, direction)) in directions.iter_mut().zip(keyed) {
+            *slot = direction;
+        }
+    }
+
+    /// Returns `directions` out of a tile entered in `previous`, in a biased random order.
+    pub(super) fn shuffled<R: Rng + ?Sized>(
+        self,
+        previous: Option<EdgeDirection>,
+        rng: &mut R,
+    ) -> [EdgeDirection; 6] {
+        let mut directions = EdgeDirection::ALL_DIRECTIONS;
+        self.shuffle(&mut directions, previous, rng);
+        directions
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn uniform_matches_plain_shuffle() {
+        let mut expected = EdgeDirection::ALL_DIRECTIONS;
+        expected.shuffle(&mut StdRng::seed_from_u64(12345));
+
+        let directions = DirectionBias::Uniform.shuffled(None, &mut StdRng::seed_from_u64(12345));
+        assert_eq!(directions, expected);
+    }
+
+    #[test]
+    fn weighted_directions_come_first() {
+        let mut rng = StdRng::seed_from_u64(12345);
+        let straight = DirectionBias::Straight { weight: 50.0 };
+        let axis = DirectionBias::Axis {
+            direction: EdgeDirection::FLAT_NORTH,
+            weight: 50.0,
+        };
+
+        let mut straight_first = 0;
+        let mut axis_first = 0;
+        for _ in 0..100 {
+            let directions = straight.shuffled(Some(EdgeDirection::FLAT_SOUTH_EAST), &mut rng);
+            straight_first += usize::from(directions[0] == EdgeDirection::FLAT_SOUTH_EAST);
+            let directions = axis.shuffled(None, &mut rng);
+            axis_first += usize::from(matches!(
+                directions[0],
+                EdgeDirection::FLAT_NORTH | EdgeDirection::FLAT_SOUTH
+            ));
+        }
+        assert!(
+            straight_first > 80,
+            "Straight was first {straight_first} times"
+        );
+        assert!(axis_first > 80, "Axis was first {axis_first} times");
+    }
+
+    #[test]
+    fn zero_weight_comes_last() {
+        let mut rng = StdRng::seed_from_u64(12345);
+        let bias = DirectionBias::Straight { weight: 0.0 };
+        for _ in 0..20 {
+            let directions = bias.shuffled(Some(EdgeDirection::FLAT_NORTH), &mut rng);
+            assert_eq!(directions[5], EdgeDirection::FLAT_NORTH);
+        }
+    }
+}
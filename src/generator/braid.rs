@@ -0,0 +1,216 @@
+use super::{
+    carve, create_rng, sorted_tiles,
+    steps::{StepLog, StepSink},
+};
+use crate::{GenerationStep, Maze};
+use hexx::{EdgeDirection, Hex};
+use rand::{seq::SliceRandom, Rng};
+
+impl Maze {
+    /// Removes a fraction of the dead ends by carving each into one of its neighbors.
+    ///
+    /// Neighbors that are dead ends themselves are preferred, so a single passage can remove
+    /// two dead ends at once. The resulting maze contains loops, while keeping its overall
+    /// structure.
+    ///
+    /// # Arguments
+    ///
+    /// - `fraction` - The fraction of dead ends to remove, between `0.0` and `1.0`.
+    /// - `seed` - The random seed used to pick the dead ends and neighbors.
+    ///
+    /// # Returns
+    ///
+    /// The passages that were carved, in order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use hexlab::prelude::*;
+    ///
+    /// let mut maze = MazeBuilder::new()
+    ///     .with_radius(5)
+    ///     .with_seed(12345)
+    ///     .build()
+    ///     .expect("Failed to create maze");
+    /// assert!(!maze.dead_ends().is_empty());
+    ///
+    /// maze.braid(1.0, Some(12345));
+    /// assert!(maze.dead_ends().is_empty());
+    /// ```
+    pub fn braid(&mut self, fraction: f32, seed: Option<u64>) -> Vec<GenerationStep> {
+        let mut rng = create_rng(seed);
+        let mut steps = StepLog::default();
+        braid(self, fraction, &mut rng, &mut steps);
+        steps.into_steps()
+    }
+
+    /// Returns the positions of all dead ends, tiles with a single passage to another tile.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use hexlab::prelude::*;
+    ///
+    /// let mut maze = Maze::from([Hex::ZERO, Hex::new(1, 0), Hex::new(2, 0)]);
+    /// maze.open_shared_walls();
+    ///
+    /// let mut dead_ends = maze.dead_ends();
+    /// dead_ends.sort_by_key(|hex| hex.x);
+    /// assert_eq!(dead_ends, vec![Hex::ZERO, Hex::new(2, 0)]);
+    /// ```
+    #[must_use]
+    pub fn dead_ends(&self) -> Vec<Hex> {
+        self.keys()
+            .copied()
+            .filter(|&pos| is_dead_end(self, pos))
+            .collect()
+    }
+}
+
+/// Carves `fraction` of the dead ends, in random order, into one of their walled neighbors.
+#[allow(
+    clippy::cast_possible_truncation,
+    clippy::cast_sign_loss,
+    clippy::cast_precision_loss
+)]
+pub fn braid<R: Rng + ?Sized>(
+    maze: &mut Maze,
+    fraction: f32,
+    rng: &mut R,
+    steps: &mut impl StepSink,
+) {
+    let mut dead_ends = sorted_tiles(maze.keys().copied())
+        .into_iter()
+        .filter(|&pos| is_dead_end(maze, pos))
+        .collect::<Vec<_>>();
+    dead_ends.shuffle(rng);
+    let count = (fraction.clamp(0.0, 1.0) * dead_ends.len() as f32).round() as usize;
+
+    for pos in dead_ends.into_iter().take(count) {
+        // Carving into a previous dead end may have already removed this one
+        if !is_dead_end(maze, pos) {
+            continue;
+        }
+
+        let walled = EdgeDirection::ALL_DIRECTIONS
+            .into_iter()
+            .filter_map(|direction| Some((maze.neighbor(pos, direction)?, direction)))
+            .filter(|&(_, direction)| {
+                maze.get_walls(&pos)
+                    .is_some_and(|walls| walls.contains(direction))
+            })
+            .collect::<Vec<_>>();
+        let preferred = walled
+            .iter()
+            .copied()
+            .filter(|&(neighbor, _)| is_dead_end(maze, neighbor))
+            .collect::<Vec<_>>();
+
+        let candidates = if preferred.is_empty() {
+            walled
+        } else {
+            preferred
+        };
+        if let Some(&(_, direction)) = candidates.choose(rng) {
+            carve(maze, pos, direction, steps);
+        }
+    }
+}
+
+/// Returns `true` if the tile at `pos` has exactly one passage to another tile.
+fn is_dead_end(maze: &Maze, pos: Hex) -> bool {
+    maze.get_walls(&pos).is_some_and(|walls| {
+        EdgeDirection::ALL_DIRECTIONS
+            .into_iter()
+            .filter(|&direction| {
+                !walls.contains(direction) && maze.neighbor(pos, direction).is_some()
+            })
+            .count()
+            == 1
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::builder::create_hex_maze;
+    use crate::GeneratorType;
+    use claims::assert_some;
+    use rand::{rngs::StdRng, SeedableRng};
+    use rstest::rstest;
+
+    fn perfect_maze() -> Maze {
+        let mut maze = create_hex_maze(6);
+        GeneratorType::RecursiveBacktracking.generate(&mut maze, None, Some(12345));
+        maze
+    }
+
+    #[rstest]
+    #[case(0.0)]
+    #[case(0.5)]
+    #[case(1.0)]
+    fn braid_removes_fraction_of_dead_ends(#[case] fraction: f32) {
+        let mut maze = perfect_maze();
+        let before = maze.dead_ends().len();
+        let mut rng = StdRng::seed_from_u64(12345);
+
+        braid(&mut maze, fraction, &mut rng, &mut ());
+
+        let removed = before - maze.dead_ends().len();
+        #[allow(
+            clippy::cast_precision_loss,
+            clippy::cast_possible_truncation,
+            clippy::cast_sign_loss
+        )]
+        let expected = (fraction * before as f32).round() as usize;
+        assert!(
+            removed >= expected,
+            "Expected at least {expected} dead ends to be removed, removed {removed}"
+        );
+    }
+
+    #[test]
+    fn braid_only_removes_walls() {
+        let original = perfect_maze();
+        let mut maze = original.clone();
+        let mut log = StepLog::default();
+
+        braid(&mut maze, 1.0, &mut StdRng::seed_from_u64(12345), &mut log);
+
+        let steps = log.into_steps();
+
+        assert!(!steps.is_empty());
+        for step in steps {
+            assert!(assert_some!(original.get_walls(&step.from)).contains(step.direction));
+            assert!(!assert_some!(maze.get_walls(&step.from)).contains(step.direction));
+            assert!(!assert_some!(maze.get_walls(&step.to)).contains(step.direction.const_neg()));
+        }
+    }
+
+    #[test]
+    fn braid_prefers_dead_end_neighbors() {
+        let original = perfect_maze();
+        let dead_ends = original.dead_ends();
+
+        for seed in 0..20 {
+            let mut maze = original.clone();
+            let mut log = StepLog::default();
+            braid(&mut maze, 1.0, &mut StdRng::seed_from_u64(seed), &mut log);
+            let steps = log.into_steps();
+
+            let first = steps[0];
+            let has_dead_end_neighbor =
+                EdgeDirection::ALL_DIRECTIONS.into_iter().any(|direction| {
+                    assert_some!(original.get_walls(&first.from)).contains(direction)
+                        && dead_ends.contains(&(first.from + direction))
+                });
+            if has_dead_end_neighbor {
+                assert!(
+                    dead_ends.contains(&first.to),
+                    "Dead end {:?} should be carved into a neighboring dead end",
+                    first.from
+                );
+            }
+        }
+    }
+}
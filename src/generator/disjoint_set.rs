@@ -0,0 +1,63 @@
+/// A disjoint-set (union-find) over the indices `0..len`, using path halving and union by size.
+#[derive(Debug, Clone)]
+pub(super) struct DisjointSet {
+    parent: Vec<usize>,
+    size: Vec<usize>,
+}
+
+impl DisjointSet {
+    /// Creates a disjoint-set where every element is in its own set.
+    pub(super) fn new(len: usize) -> Self {
+        Self {
+            parent: (0..len).collect(),
+            size: vec![1; len],
+        }
+    }
+
+    /// Returns the representative of the set containing `element`.
+    pub(super) fn find(&mut self, mut element: usize) -> usize {
+        while self.parent[element] != element {
+            self.parent[element] = self.parent[self.parent[element]];
+            element = self.parent[element];
+        }
+        element
+    }
+
+    /// Merges the sets containing `a` and `b`.
+    ///
+    /// Returns `true` if the elements were in different sets, `false` otherwise.
+    pub(super) fn union(&mut self, a: usize, b: usize) -> bool {
+        let (mut a, mut b) = (self.find(a), self.find(b));
+        if a == b {
+            return false;
+        }
+        if self.size[a] < self.size[b] {
+            std::mem::swap(&mut a, &mut b);
+        }
+        self.parent[b] = a;
+        self.size[a] += self.size[b];
+        true
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn disjoint_set_union_find() {
+        let mut set = DisjointSet::new(5);
+
+        assert!(set.union(0, 1));
+        assert!(set.union(3, 4));
+        assert!(!set.union(1, 0));
+
+        assert_eq!(set.find(0), set.find(1));
+        assert_eq!(set.find(3), set.find(4));
+        assert_ne!(set.find(0), set.find(3));
+        assert_ne!(set.find(2), set.find(0));
+
+        assert!(set.union(1, 4));
+        assert_eq!(set.find(0), set.find(3));
+    }
+}
@@ -0,0 +1,197 @@
+use super::{
+    components,
+    disjoint_set::DisjointSet,
+    sorted_tiles,
+    steps::{GenerationStep, StepSink},
+};
+use crate::{Maze, Topology};
+use hexx::{EdgeDirection, Hex};
+use rand::{seq::SliceRandom, Rng, RngCore};
+use std::collections::{HashMap, HashSet};
+
+pub(super) fn generate_recursive_division(
+    maze: &mut Maze,
+    rng: &mut dyn RngCore,
+    steps: &mut impl StepSink,
+) {
+    // The cuts run along straight axes, which do not line up across wrapped edges, so the maze
+    // is divided as if it were bounded and walls across the seams are left in place
+    let topology = maze.topology();
+    maze.set_topology(Topology::Bounded);
+    maze.open_shared_walls();
+    recursive_division(maze, rng, steps);
+    maze.set_topology(topology);
+}
+
+/// Divides the maze into ever smaller regions by adding walls, until every region is a
+/// single tile.
+///
+/// Each division cuts a region along one of the three hex axes and walls off the cut,
+/// leaving a single gap between each pair of pieces that must stay connected.
+///
+/// Every passage of the final maze is a gap left by some division, so the gaps are recorded as
+/// the steps carving the same maze from fully walled tiles.
+fn recursive_division<R: Rng + ?Sized>(maze: &mut Maze, rng: &mut R, steps: &mut impl StepSink) {
+    let tiles = sorted_tiles(maze.keys().copied());
+    let mut regions = components(maze, &tiles, |_| true);
+
+    while let Some(region) = regions.pop() {
+        if region.len() < 2 {
+            continue;
+        }
+
+        let axis = pick_axis(&region, rng);
+        let (min, max) = extent(&region, axis);
+        let cut = rng.gen_range(min..max);
+        let in_region = region.iter().copied().collect::<HashSet<_>>();
+
+        // Pieces on either side of the cut, which may consist of several components if the
+        // region is not convex
+        let pieces = components(maze, &region, |hex| axis.coord(hex) <= cut);
+        let piece_of = pieces
+            .iter()
+            .enumerate()
+            .flat_map(|(index, piece)| piece.iter().map(move |&hex| (hex, index)))
+            .collect::<HashMap<_, _>>();
+
+        // Edges crossing the cut, grouped by the pair of pieces they connect
+        let mut crossings = HashMap::<(usize, usize), Vec<(Hex, EdgeDirection)>>::new();
+        for &hex in region.iter().filter(|&&hex| axis.coord(hex) <= cut) {
+            for direction in EdgeDirection::ALL_DIRECTIONS {
+                let neighbor = hex + direction;
+                if in_region.contains(&neighbor) && axis.coord(neighbor) > cut {
+                    crossings
+                        .entry((piece_of[&hex], piece_of[&neighbor]))
+                        .or_default()
+                        .push((hex, direction));
+                }
+            }
+        }
+        let mut crossings = crossings.into_iter().collect::<Vec<_>>();
+        crossings.sort_unstable_by_key(|&(pieces, _)| pieces);
+        crossings.shuffle(rng);
+
+        // Wall off every crossing edge, except for a single gap between pieces that are not
+        // yet connected
+        let mut connected = DisjointSet::new(pieces.len());
+        for ((a, b), edges) in crossings {
+            let gap = connected.union(a, b).then(|| rng.gen_range(0..edges.len()));
+            for (index, &(hex, direction)) in edges.iter().enumerate() {
+                if Some(index) == gap {
+                    steps.record(GenerationStep::new(hex, direction));
+                } else {
+                    let _ = maze.wall_up(&hex, direction);
+                }
+            }
+        }
+
+        regions.extend(pieces);
+    }
+}
+
+/// One of the three axes of cube coordinates.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Axis {
+    X,
+    Y,
+    Z,
+}
+
+impl Axis {
+    const ALL: [Self; 3] = [Self::X, Self::Y, Self::Z];
+
+    const fn coord(self, hex: Hex) -> i32 {
+        match self {
+            Self::X => hex.x,
+            Self::Y => hex.y,
+            Self::Z => hex.z(),
+        }
+    }
+}
+
+/// Returns the minimum and maximum coordinate of `region` along `axis`.
+fn extent(region: &[Hex], axis: Axis) -> (i32, i32) {
+    region
+        .iter()
+        .fold((i32::MAX, i32::MIN), |(min, max), &hex| {
+            let coord = axis.coord(hex);
+            (min.min(coord), max.max(coord))
+        })
+}
+
+/// Picks the axis along which `region` is the longest, breaking ties randomly.
+fn pick_axis<R: Rng + ?Sized>(region: &[Hex], rng: &mut R) -> Axis {
+    let lengths = Axis::ALL.map(|axis| {
+        let (min, max) = extent(region, axis);
+        (axis, max - min)
+    });
+    let longest = lengths.iter().map(|&(_, length)| length).max();
+    let candidates = lengths
+        .iter()
+        .filter(|&&(_, length)| Some(length) == longest)
+        .map(|&(axis, _)| axis)
+        .collect::<Vec<_>>();
+    candidates.choose(rng).copied().unwrap_or(Axis::X)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        builder::create_hex_maze,
+        generator::{connected_tiles, open_passages},
+    };
+    use claims::assert_some;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn recursive_division_spanning_tree() {
+        let mut maze = create_hex_maze(5);
+        maze.open_shared_walls();
+        let mut rng = StdRng::seed_from_u64(12345);
+
+        recursive_division(&mut maze, &mut rng, &mut ());
+
+        assert_eq!(
+            connected_tiles(&maze, Hex::ZERO).len(),
+            maze.count(),
+            "All tiles should be connected"
+        );
+        assert_eq!(
+            open_passages(&maze),
+            maze.count() - 1,
+            "Maze should be a spanning tree"
+        );
+    }
+
+    #[test]
+    fn recursive_division_concave_shape() {
+        // A ring of tiles, which is split into disconnected pieces by any cut
+        let mut maze = Hex::ZERO.ring(3).collect::<Maze>();
+        maze.open_shared_walls();
+        let mut rng = StdRng::seed_from_u64(12345);
+
+        recursive_division(&mut maze, &mut rng, &mut ());
+
+        let start = assert_some!(Hex::ZERO.ring(3).next());
+        assert_eq!(
+            connected_tiles(&maze, start).len(),
+            maze.count(),
+            "All tiles should be connected"
+        );
+        assert_eq!(
+            open_passages(&maze),
+            maze.count() - 1,
+            "Maze should be a spanning tree"
+        );
+    }
+
+    #[test]
+    fn components_split_by_side() {
+        let maze = create_hex_maze(2);
+        let tiles = maze.keys().copied().collect::<Vec<_>>();
+
+        assert_eq!(components(&maze, &tiles, |_| true).len(), 1);
+        assert_eq!(components(&maze, &tiles, |hex| hex.x <= 0).len(), 2);
+    }
+}
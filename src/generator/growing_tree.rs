@@ -0,0 +1,178 @@
+use super::{carve, steps::StepSink};
+use crate::{DirectionBias, Maze};
+use hexx::Hex;
+use rand::Rng;
+use std::collections::{HashSet, VecDeque};
+
+/// Selects which active tile the growing tree generator continues carving from.
+///
+/// The policy determines the texture of the maze: always picking the newest tile behaves like
+/// recursive backtracking, while picking a random tile behaves like Prim's algorithm.
+///
+/// # Examples
+///
+/// ```
+/// use hexlab::prelude::*;
+///
+/// let maze = MazeBuilder::new()
+///     .with_radius(3)
+///     .with_generator(GeneratorType::GrowingTree {
+///         policy: GrowingTreePolicy::Mixed { newest: 75 },
+///     })
+///     .build()
+///     .expect("Failed to create maze");
+///
+/// assert_eq!(maze.count(), 37);
+/// ```
+#[allow(clippy::module_name_repetitions)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "bevy_reflect", derive(bevy_reflect::Reflect))]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum GrowingTreePolicy {
+    /// Always continue from the most recently added tile, producing long corridors.
+    #[default]
+    Newest,
+    /// Always continue from the earliest added tile, producing long straight passages.
+    Oldest,
+    /// Continue from a random tile, producing short, highly branching dead ends.
+    Random,
+    /// Continue from the newest tile in `newest` percent (between `0` and `100`) of the
+    /// picks, otherwise from a random tile.
+    ///
+    /// Values above `100` are treated as `100`.
+    Mixed { newest: u8 },
+}
+
+impl GrowingTreePolicy {
+    /// Returns the index of the next active tile, given a non-empty list of `len` active tiles.
+    fn select<R: Rng + ?Sized>(self, len: usize, rng: &mut R) -> usize {
+        match self {
+            Self::Newest => len - 1,
+            Self::Oldest => 0,
+            Self::Random => rng.gen_range(0..len),
+            Self::Mixed { newest } => {
+                if rng.gen_ratio(u32::from(newest.min(100)), 100) {
+                    len - 1
+                } else {
+                    rng.gen_range(0..len)
+                }
+            }
+        }
+    }
+}
+
+pub(super) fn growing_tree<R: Rng + ?Sized>(
+    maze: &mut Maze,
+    start: Hex,
+    policy: GrowingTreePolicy,
+    bias: DirectionBias,
+    rng: &mut R,
+    steps: &mut impl StepSink,
+) {
+    let mut visited = HashSet::from([start]);
+    // Active tiles with the direction they were entered in
+    let mut active = VecDeque::from([(start, None)]);
+
+    while !active.is_empty() {
+        let index = policy.select(active.len(), rng);
+        let (current, previous) = active[index];
+
+        let directions = bias.shuffled(previous, rng);
+        let next = directions.into_iter().find_map(|direction| {
+            let neighbor = maze.neighbor(current, direction)?;
+            (!visited.contains(&neighbor)).then_some((neighbor, direction))
+        });
+
+        if let Some((neighbor, direction)) = next {
+            carve(maze, current, direction, steps);
+            visited.insert(neighbor);
+            active.push_back((neighbor, Some(direction)));
+        } else if index + 1 == active.len() {
+            active.pop_back();
+        } else {
+            // Policies only rely on the position of the newest tile, so the front fills the gap
+            active.swap_remove_front(index);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{builder::create_hex_maze, generator::connected_tiles};
+    use rand::{rngs::StdRng, SeedableRng};
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(GrowingTreePolicy::Newest)]
+    #[case(GrowingTreePolicy::Oldest)]
+    #[case(GrowingTreePolicy::Random)]
+    #[case(GrowingTreePolicy::Mixed { newest: 75 })]
+    #[case(GrowingTreePolicy::Mixed { newest: u8::MAX })]
+    fn growing_tree_connectivity(#[case] policy: GrowingTreePolicy) {
+        let mut maze = create_hex_maze(4);
+        let mut rng = StdRng::seed_from_u64(12345);
+
+        growing_tree(
+            &mut maze,
+            Hex::new(1, -1),
+            policy,
+            DirectionBias::Uniform,
+            &mut rng,
+            &mut (),
+        );
+
+        assert_eq!(
+            connected_tiles(&maze, Hex::new(1, -1)).len(),
+            maze.count(),
+            "All tiles should be connected"
+        );
+    }
+
+    #[rstest]
+    #[case(5, GrowingTreePolicy::Newest, 4)]
+    #[case(5, GrowingTreePolicy::Oldest, 0)]
+    fn growing_tree_policy_select(
+        #[case] len: usize,
+        #[case] policy: GrowingTreePolicy,
+        #[case] expected: usize,
+    ) {
+        let mut rng = StdRng::seed_from_u64(12345);
+        assert_eq!(policy.select(len, &mut rng), expected);
+    }
+
+    #[test]
+    fn growing_tree_policy_out_of_range() {
+        let mut rng = StdRng::seed_from_u64(12345);
+        let policy = GrowingTreePolicy::Mixed { newest: u8::MAX };
+        for _ in 0..100 {
+            assert_eq!(policy.select(5, &mut rng), 4);
+        }
+    }
+
+    #[test]
+    fn growing_tree_deterministic() {
+        let policy = GrowingTreePolicy::Mixed { newest: 50 };
+        let mut maze1 = create_hex_maze(5);
+        let mut maze2 = create_hex_maze(5);
+
+        growing_tree(
+            &mut maze1,
+            Hex::ZERO,
+            policy,
+            DirectionBias::Uniform,
+            &mut StdRng::seed_from_u64(12345),
+            &mut (),
+        );
+        growing_tree(
+            &mut maze2,
+            Hex::ZERO,
+            policy,
+            DirectionBias::Uniform,
+            &mut StdRng::seed_from_u64(12345),
+            &mut (),
+        );
+
+        assert_eq!(maze1, maze2, "Same seed should produce identical mazes");
+    }
+}
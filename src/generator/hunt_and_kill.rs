@@ -0,0 +1,150 @@
+use super::{carve, sorted_tiles, steps::StepSink};
+use crate::{DirectionBias, Maze};
+use hexx::{EdgeDirection, Hex};
+use rand::{seq::SliceRandom, Rng};
+use std::collections::HashSet;
+
+pub(super) fn hunt_and_kill<R: Rng + ?Sized>(
+    maze: &mut Maze,
+    start: Hex,
+    bias: DirectionBias,
+    rng: &mut R,
+    steps: &mut impl StepSink,
+) {
+    let tiles = sorted_tiles(maze.keys().copied());
+    let mut visited = HashSet::from([start]);
+    // Tiles before the cursor are all visited, so hunting can skip them
+    let mut cursor = 0;
+
+    // The current tile with the direction it was entered in
+    let mut current = Some((start, None));
+    while let Some((tile, previous)) = current {
+        current = walk(maze, tile, previous, bias, &mut visited, rng, steps)
+            .map(|(neighbor, direction)| (neighbor, Some(direction)))
+            .or_else(|| {
+                while tiles.get(cursor).is_some_and(|hex| visited.contains(hex)) {
+                    cursor += 1;
+                }
+                hunt(maze, &tiles[cursor..], &mut visited, rng, steps)
+                    .map(|(hex, direction)| (hex, Some(direction)))
+            });
+    }
+}
+
+/// Carves from `current`, entered in `previous`, into a random unvisited neighbor.
+///
+/// Returns the neighbor with the direction it was entered in, or `None` if every neighbor is
+/// already visited.
+fn walk<R: Rng + ?Sized>(
+    maze: &mut Maze,
+    current: Hex,
+    previous: Option<EdgeDirection>,
+    bias: DirectionBias,
+    visited: &mut HashSet<Hex>,
+    rng: &mut R,
+    steps: &mut impl StepSink,
+) -> Option<(Hex, EdgeDirection)> {
+    let directions = bias.shuffled(previous, rng);
+
+    let (neighbor, direction) = directions.into_iter().find_map(|direction| {
+        let neighbor = maze.neighbor(current, direction)?;
+        (!visited.contains(&neighbor)).then_some((neighbor, direction))
+    })?;
+    carve(maze, current, direction, steps);
+    visited.insert(neighbor);
+    Some((neighbor, direction))
+}
+
+/// Scans `tiles` for the first unvisited tile adjacent to the visited region and carves it
+/// into a random visited neighbor.
+///
+/// Returns the tile with the direction it was entered in, or `None` if no such tile is left.
+fn hunt<R: Rng + ?Sized>(
+    maze: &mut Maze,
+    tiles: &[Hex],
+    visited: &mut HashSet<Hex>,
+    rng: &mut R,
+    steps: &mut impl StepSink,
+) -> Option<(Hex, EdgeDirection)> {
+    let (hex, neighbor, direction) =
+        tiles
+            .iter()
+            .filter(|hex| !visited.contains(hex))
+            .find_map(|&hex| {
+                let directions = EdgeDirection::ALL_DIRECTIONS
+                    .into_iter()
+                    .filter_map(|direction| Some((maze.neighbor(hex, direction)?, direction)))
+                    .filter(|(neighbor, _)| visited.contains(neighbor))
+                    .collect::<Vec<_>>();
+                directions
+                    .choose(rng)
+                    .map(|&(neighbor, direction)| (hex, neighbor, direction))
+            })?;
+    carve(maze, neighbor, direction.const_neg(), steps);
+    visited.insert(hex);
+    Some((hex, direction.const_neg()))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{builder::create_hex_maze, generator::connected_tiles};
+    use claims::assert_some;
+    use rand::{rngs::StdRng, SeedableRng};
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(Hex::ZERO)]
+    #[case(Hex::new(1, -1))]
+    #[case(Hex::new(-2, 2))]
+    fn hunt_and_kill_connectivity(#[case] start: Hex) {
+        let mut maze = create_hex_maze(4);
+        let mut rng = StdRng::seed_from_u64(12345);
+
+        hunt_and_kill(&mut maze, start, DirectionBias::Uniform, &mut rng, &mut ());
+
+        assert_eq!(
+            connected_tiles(&maze, start).len(),
+            maze.count(),
+            "All tiles should be connected"
+        );
+    }
+
+    #[test]
+    fn hunt_finds_tile_next_to_visited_region() {
+        let mut maze = create_hex_maze(2);
+        let mut visited = HashSet::from([Hex::ZERO]);
+        let tiles = sorted_tiles(maze.keys().copied());
+        let mut rng = StdRng::seed_from_u64(12345);
+
+        let (found, direction) =
+            assert_some!(hunt(&mut maze, &tiles, &mut visited, &mut rng, &mut ()));
+
+        assert_eq!(found.unsigned_distance_to(Hex::ZERO), 1);
+        assert!(visited.contains(&found));
+        assert_eq!(found - direction, Hex::ZERO);
+    }
+
+    #[test]
+    fn hunt_and_kill_deterministic() {
+        let mut maze1 = create_hex_maze(5);
+        let mut maze2 = create_hex_maze(5);
+
+        hunt_and_kill(
+            &mut maze1,
+            Hex::ZERO,
+            DirectionBias::Uniform,
+            &mut StdRng::seed_from_u64(12345),
+            &mut (),
+        );
+        hunt_and_kill(
+            &mut maze2,
+            Hex::ZERO,
+            DirectionBias::Uniform,
+            &mut StdRng::seed_from_u64(12345),
+            &mut (),
+        );
+
+        assert_eq!(maze1, maze2, "Same seed should produce identical mazes");
+    }
+}
@@ -0,0 +1,76 @@
+use super::{carve, disjoint_set::DisjointSet, shared_walls, sorted_tiles, steps::StepSink};
+use crate::Maze;
+use rand::{seq::SliceRandom, Rng};
+use std::collections::HashMap;
+
+pub(super) fn randomized_kruskal<R: Rng + ?Sized>(
+    maze: &mut Maze,
+    rng: &mut R,
+    steps: &mut impl StepSink,
+) {
+    let tiles = sorted_tiles(maze.keys().copied());
+    let indices = tiles
+        .iter()
+        .enumerate()
+        .map(|(index, &hex)| (hex, index))
+        .collect::<HashMap<_, _>>();
+
+    let mut edges = shared_walls(maze, &tiles).collect::<Vec<_>>();
+    edges.shuffle(rng);
+
+    let mut sets = DisjointSet::new(tiles.len());
+    for (hex, neighbor, direction) in edges {
+        if sets.union(indices[&hex], indices[&neighbor]) {
+            carve(maze, hex, direction, steps);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{builder::create_hex_maze, generator::connected_tiles};
+    use hexx::Hex;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn randomized_kruskal_connectivity() {
+        let mut maze = create_hex_maze(3);
+        let mut rng = StdRng::seed_from_u64(12345);
+
+        randomized_kruskal(&mut maze, &mut rng, &mut ());
+
+        assert!(
+            connected_tiles(&maze, Hex::ZERO).len() == maze.count(),
+            "All tiles should be connected"
+        );
+    }
+
+    #[test]
+    fn randomized_kruskal_irregular_shape() {
+        // An L-shaped strip that does not contain the origin
+        let mut maze = (1..6)
+            .map(|q| Hex::new(q, 0))
+            .chain((1..4).map(|r| Hex::new(5, r)))
+            .collect::<Maze>();
+        let mut rng = StdRng::seed_from_u64(12345);
+
+        randomized_kruskal(&mut maze, &mut rng, &mut ());
+
+        assert!(
+            connected_tiles(&maze, Hex::new(1, 0)).len() == maze.count(),
+            "All tiles should be connected"
+        );
+    }
+
+    #[test]
+    fn randomized_kruskal_deterministic() {
+        let mut maze1 = create_hex_maze(5);
+        let mut maze2 = create_hex_maze(5);
+
+        randomized_kruskal(&mut maze1, &mut StdRng::seed_from_u64(12345), &mut ());
+        randomized_kruskal(&mut maze2, &mut StdRng::seed_from_u64(12345), &mut ());
+
+        assert_eq!(maze1, maze2, "Same seed should produce identical mazes");
+    }
+}
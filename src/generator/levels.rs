@@ -0,0 +1,130 @@
+use super::{disjoint_set::DisjointSet, shared_walls, sorted_tiles};
+use crate::LayeredMaze;
+use rand::{seq::SliceRandom, Rng};
+use std::collections::HashMap;
+
+/// Generates a single maze across all levels with randomized Kruskal's algorithm.
+///
+/// Passages within a level and stairs between tiles at the same position on consecutive levels
+/// are candidates alike, so the result is a spanning tree of every tile on every level. Levels
+/// may be joined by any number of stairs, and a level on its own may be split into several
+/// parts. Tiles that cannot be reached at all are left in separate trees.
+pub fn generate_layered<R: Rng + ?Sized>(maze: &mut LayeredMaze, rng: &mut R) {
+    let coords = maze
+        .levels()
+        .iter()
+        .enumerate()
+        .flat_map(|(level, tiles)| {
+            sorted_tiles(tiles.keys().copied())
+                .into_iter()
+                .map(move |hex| (hex, level))
+        })
+        .collect::<Vec<_>>();
+    let indices = coords
+        .iter()
+        .enumerate()
+        .map(|(index, &coord)| (coord, index))
+        .collect::<HashMap<_, _>>();
+
+    // Each edge joins a tile to a neighbor on its level through the wall in a direction, or to
+    // the tile above it along stairs
+    let mut edges = Vec::new();
+    for (level, tiles) in maze.levels().iter().enumerate() {
+        let sorted = sorted_tiles(tiles.keys().copied());
+        edges.extend(
+            shared_walls(tiles, &sorted).map(|(hex, neighbor, direction)| {
+                ((hex, level), (neighbor, level), Some(direction))
+            }),
+        );
+        edges.extend(
+            sorted
+                .iter()
+                .filter(|&&hex| indices.contains_key(&(hex, level + 1)))
+                .map(|&hex| ((hex, level), (hex, level + 1), None)),
+        );
+    }
+    edges.shuffle(rng);
+
+    let mut sets = DisjointSet::new(coords.len());
+    for (coord, other, direction) in edges {
+        if !sets.union(indices[&coord], indices[&other]) {
+            continue;
+        }
+        let (hex, level) = coord;
+        match direction {
+            Some(direction) => {
+                if let Some(tiles) = maze.level_mut(level) {
+                    let _ = tiles.carve(&hex, direction);
+                }
+            }
+            None => {
+                let _ = maze.add_stairs(&coord);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{builder::create_hex_maze, Maze};
+    use hexx::Hex;
+    use rand::{rngs::StdRng, SeedableRng};
+    use std::collections::HashSet;
+
+    /// Returns the tiles reachable from `start` through passages and stairs, and the number of
+    /// passages and stairs between them.
+    fn connected_levels(maze: &LayeredMaze, start: (Hex, usize)) -> (usize, usize) {
+        let mut connected = HashSet::from([start]);
+        let mut to_visit = vec![start];
+        let mut passages = 0;
+        while let Some(current) = to_visit.pop() {
+            for next in maze.passages(&current) {
+                passages += 1;
+                if connected.insert(next) {
+                    to_visit.push(next);
+                }
+            }
+        }
+        (connected.len(), passages / 2)
+    }
+
+    #[test]
+    fn generate_layered_is_spanning_tree() {
+        let mut maze = (0..4).map(|_| create_hex_maze(2)).collect::<LayeredMaze>();
+        generate_layered(&mut maze, &mut StdRng::seed_from_u64(12345));
+
+        let (tiles, passages) = connected_levels(&maze, (Hex::ZERO, 0));
+        assert_eq!(tiles, maze.count(), "All levels should be connected");
+        assert_eq!(passages, maze.count() - 1, "Maze should be a spanning tree");
+        for level in 0..3 {
+            assert!(maze.stairs().iter().any(|&(_, below)| below == level));
+        }
+    }
+
+    #[test]
+    fn generate_layered_uses_shared_tiles() {
+        let mut maze = [
+            Maze::from([Hex::ZERO, Hex::new(1, 0)]),
+            Maze::from([Hex::new(1, 0), Hex::new(2, 0)]),
+            Maze::from([Hex::new(5, 5)]),
+        ]
+        .into_iter()
+        .collect::<LayeredMaze>();
+        generate_layered(&mut maze, &mut StdRng::seed_from_u64(12345));
+
+        assert_eq!(maze.stairs(), vec![(Hex::new(1, 0), 0)]);
+        assert_eq!(connected_levels(&maze, (Hex::ZERO, 0)), (4, 3));
+    }
+
+    #[test]
+    fn generate_layered_deterministic() {
+        let generate = || {
+            let mut maze = (0..3).map(|_| create_hex_maze(3)).collect::<LayeredMaze>();
+            generate_layered(&mut maze, &mut StdRng::seed_from_u64(12345));
+            maze
+        };
+
+        assert_eq!(generate(), generate());
+    }
+}
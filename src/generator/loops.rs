@@ -0,0 +1,236 @@
+use super::{
+    carve, create_rng, shared_walls, sorted_tiles,
+    steps::{StepLog, StepSink},
+};
+use crate::{GenerationStep, Maze};
+use hexx::Hex;
+use rand::{seq::SliceRandom, Rng};
+use std::collections::{HashSet, VecDeque};
+
+impl Maze {
+    /// Opens additional walls, each creating a loop of at least `min_length` tiles.
+    ///
+    /// The length of a loop is the number of tiles on the shortest cycle through the opened
+    /// wall, so a `min_length` of 6 or more rules out shortcuts between tiles that were
+    /// already close to each other. Fewer than `count` walls are opened if the maze runs out
+    /// of walls that satisfy the minimum length.
+    ///
+    /// # Arguments
+    ///
+    /// - `count` - The number of walls to open.
+    /// - `min_length` - The minimum number of tiles in each new loop.
+    /// - `seed` - The random seed used to pick the walls.
+    ///
+    /// # Returns
+    ///
+    /// The passages that were carved, in order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use hexlab::prelude::*;
+    ///
+    /// let mut maze = MazeBuilder::new()
+    ///     .with_radius(5)
+    ///     .with_seed(12345)
+    ///     .build()
+    ///     .expect("Failed to create maze");
+    ///
+    /// let opened = maze.add_loops(5, 8, Some(12345));
+    /// assert_eq!(opened.len(), 5);
+    /// for step in opened {
+    ///     assert!(!maze.get_walls(&step.from).unwrap().contains(step.direction));
+    /// }
+    /// ```
+    pub fn add_loops(
+        &mut self,
+        count: usize,
+        min_length: usize,
+        seed: Option<u64>,
+    ) -> Vec<GenerationStep> {
+        let mut rng = create_rng(seed);
+        let mut steps = StepLog::default();
+        add_loops(self, count, min_length, &mut rng, &mut steps);
+        steps.into_steps()
+    }
+}
+
+/// Opens up to `count` random walls whose tiles are at least `min_length - 1` passages apart.
+pub fn add_loops<R: Rng + ?Sized>(
+    maze: &mut Maze,
+    count: usize,
+    min_length: usize,
+    rng: &mut R,
+    steps: &mut impl StepSink,
+) {
+    let tiles = sorted_tiles(maze.keys().copied());
+    let mut walls = shared_walls(maze, &tiles)
+        .filter(|&(hex, neighbor, direction)| {
+            // The walls of a crossed tile along its under-passage belong to the passage on top
+            let crossed = [hex, neighbor].iter().any(|pos| {
+                maze.get(pos)
+                    .is_some_and(|tile| tile.has_under_passage(direction))
+            });
+            !crossed
+                && maze
+                    .get_walls(&hex)
+                    .is_some_and(|walls| walls.contains(direction))
+        })
+        .collect::<Vec<_>>();
+    walls.shuffle(rng);
+
+    let mut opened = 0;
+    for (hex, neighbor, direction) in walls {
+        if opened == count {
+            break;
+        }
+        // Earlier loops may have shortened the distance, so it is checked when the wall is
+        // considered rather than up front
+        let max_distance = min_length.saturating_sub(2);
+        if !within_distance(maze, hex, neighbor, max_distance) {
+            carve(maze, hex, direction, steps);
+            opened += 1;
+        }
+    }
+}
+
+/// Returns `true` if `to` can be reached from `from` through at most `max_distance` passages.
+///
+/// Passing under a tile of a weave maze counts as entering it, see [`Maze::moves`].
+fn within_distance(maze: &Maze, from: Hex, to: Hex, max_distance: usize) -> bool {
+    let mut visited = HashSet::from([(from, None)]);
+    let mut queue = VecDeque::from([((from, None), 0)]);
+
+    while let Some(((current, under), distance)) = queue.pop_front() {
+        if current == to && under.is_none() {
+            return true;
+        }
+        if distance == max_distance {
+            continue;
+        }
+        for next in maze.moves(current, under) {
+            if visited.insert(next) {
+                queue.push_back((next, distance + 1));
+            }
+        }
+    }
+    false
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{builder::create_hex_maze, generator::weave, GeneratorType};
+    use claims::assert_some;
+    use hexx::EdgeDirection;
+    use rand::{rngs::StdRng, SeedableRng};
+    use rstest::rstest;
+
+    fn perfect_maze() -> Maze {
+        let mut maze = create_hex_maze(6);
+        GeneratorType::RecursiveBacktracking.generate(&mut maze, None, Some(12345));
+        maze
+    }
+
+    /// Returns the number of passages on the shortest path between two tiles.
+    fn distance(maze: &Maze, from: Hex, to: Hex) -> usize {
+        assert_some!((0..maze.count()).find(|&max_distance| within_distance(
+            maze,
+            from,
+            to,
+            max_distance
+        )))
+    }
+
+    #[rstest]
+    #[case(1, 3)]
+    #[case(5, 6)]
+    #[case(10, 12)]
+    fn add_loops_respects_min_length(#[case] count: usize, #[case] min_length: usize) {
+        let mut maze = perfect_maze();
+        let mut log = StepLog::default();
+
+        add_loops(
+            &mut maze,
+            count,
+            min_length,
+            &mut StdRng::seed_from_u64(12345),
+            &mut log,
+        );
+
+        let steps = log.into_steps();
+
+        assert_eq!(steps.len(), count);
+        for step in steps {
+            // Without the new passage, the loop is the shortest remaining path plus the step
+            let mut without = maze.clone();
+            let _ = without.wall_up(&step.from, step.direction);
+            assert!(
+                distance(&without, step.from, step.to) + 1 >= min_length,
+                "Loop through {step:?} is shorter than {min_length}"
+            );
+        }
+    }
+
+    #[test]
+    fn add_loops_stops_when_no_walls_qualify() {
+        let mut maze = perfect_maze();
+        let tiles = maze.count();
+        let mut log = StepLog::default();
+
+        add_loops(
+            &mut maze,
+            usize::MAX,
+            tiles + 1,
+            &mut StdRng::seed_from_u64(12345),
+            &mut log,
+        );
+
+        let steps = log.into_steps();
+
+        assert!(steps.is_empty());
+    }
+
+    #[test]
+    fn add_loops_keeps_weave_crossings() {
+        let mut maze = perfect_maze();
+        let mut rng = StdRng::seed_from_u64(12345);
+        weave(&mut maze, 1.0, &mut rng, &mut ());
+        let crossed = maze
+            .values()
+            .filter_map(|tile| Some((tile.pos, tile.under?)))
+            .collect::<Vec<_>>();
+        assert!(!crossed.is_empty());
+
+        add_loops(&mut maze, usize::MAX, 4, &mut rng, &mut ());
+
+        assert!(maze.validate().is_valid());
+        for (pos, direction) in crossed {
+            let walls = assert_some!(maze.get_walls(&pos));
+            assert!(walls.contains(direction) && walls.contains(direction.const_neg()));
+        }
+    }
+
+    #[test]
+    fn within_distance_follows_tunnels() {
+        let (west, east) = (Hex::new(-1, 0), Hex::new(1, 0));
+        let direction = assert_some!(EdgeDirection::ALL_DIRECTIONS
+            .into_iter()
+            .find(|&direction| west + direction == Hex::ZERO));
+        let mut maze = Maze::from([west, Hex::ZERO, east]);
+        GenerationStep::tunnel(west, Hex::ZERO, east, direction).apply(&mut maze);
+
+        assert!(within_distance(&maze, west, east, 2));
+        assert!(!within_distance(&maze, west, east, 1));
+        assert!(!within_distance(&maze, west, Hex::ZERO, usize::MAX));
+    }
+
+    #[test]
+    fn within_distance_limit() {
+        let mut maze = Maze::from([Hex::new(0, 0), Hex::new(1, 0), Hex::new(2, 0)]);
+        maze.open_shared_walls();
+
+        assert!(within_distance(&maze, Hex::new(0, 0), Hex::new(2, 0), 2));
+        assert!(!within_distance(&maze, Hex::new(0, 0), Hex::new(2, 0), 1));
+    }
+}
@@ -1,24 +1,329 @@
+mod aldous_broder;
 mod backtrack;
-use crate::Maze;
-use backtrack::generate_backtracking;
+mod bias;
+mod braid;
+mod disjoint_set;
+mod division;
+mod growing_tree;
+mod hunt_and_kill;
+mod kruskal;
+mod levels;
+mod loops;
+mod prims;
+mod rooms;
+mod steps;
+mod weave;
+mod wilson;
+use crate::{Maze, MazeGenerator, MazeStorage};
+use aldous_broder::aldous_broder;
+pub use backtrack::generate_backtracking;
+pub use backtrack::Visited;
 #[cfg(feature = "bevy")]
 use bevy::prelude::*;
-use hexx::Hex;
+pub use bias::DirectionBias;
+pub use braid::braid;
+use division::generate_recursive_division;
+use growing_tree::growing_tree;
+pub use growing_tree::GrowingTreePolicy;
+use hexx::{EdgeDirection, Hex};
+use hunt_and_kill::hunt_and_kill;
+use kruskal::randomized_kruskal;
+pub use levels::generate_layered;
+pub use loops::add_loops;
+use prims::randomized_prims;
+use rand::{rngs::StdRng, thread_rng, RngCore, SeedableRng};
+pub use rooms::generate_with_rooms;
+use std::collections::HashSet;
+pub use steps::{diff_steps, GenerationStep, GenerationSteps};
+pub use steps::{StepLog, StepSink};
+pub use weave::weave;
+use wilson::wilson;
 
 #[allow(clippy::module_name_repetitions)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[cfg_attr(feature = "bevy_reflect", derive(bevy_reflect::Reflect))]
 #[cfg_attr(feature = "bevy", derive(Component))]
 #[cfg_attr(feature = "bevy", reflect(Component))]
-#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
 pub enum GeneratorType {
+    /// Recursive backtracking, producing long winding corridors with few branches.
     #[default]
     RecursiveBacktracking,
+    /// Randomized Prim's algorithm, producing short, highly branching dead ends.
+    Prims,
+    /// Randomized Kruskal's algorithm, producing a uniform texture unbiased by the start position.
+    ///
+    /// Works on any set of tiles and ignores the start position.
+    Kruskal,
+    /// Wilson's algorithm, producing uniform spanning trees using loop-erased random walks.
+    ///
+    /// Every possible maze is equally likely, making it suitable when fairness matters.
+    Wilson,
+    /// Aldous-Broder algorithm, producing uniform spanning trees by random walking until
+    /// every tile is visited.
+    ///
+    /// Slow to finish on large mazes, see [`GeneratorType::AldousBroderWilson`].
+    AldousBroder,
+    /// Aldous-Broder algorithm that switches to Wilson's algorithm once `switch_at` percent
+    /// (between `0` and `100`) of the tiles are visited.
+    ///
+    /// Produces uniform spanning trees while avoiding the slow tails of both algorithms.
+    AldousBroderWilson { switch_at: u8 },
+    /// Growing tree algorithm, continuing from the active tile chosen by `policy`.
+    ///
+    /// Depending on the policy, produces textures ranging from recursive backtracking to
+    /// Prim's algorithm.
+    GrowingTree { policy: GrowingTreePolicy },
+    /// Hunt-and-kill algorithm, random walking until stuck and then scanning for an unvisited
+    /// tile next to the visited region.
+    ///
+    /// Produces long corridors like recursive backtracking without keeping a stack of tiles.
+    HuntAndKill,
+    /// Recursive division algorithm, starting from an open maze and adding walls along the
+    /// hex axes, each with a single gap.
+    ///
+    /// Produces long straight walls and ignores the start position.
+    RecursiveDivision,
 }
 impl GeneratorType {
+    /// Generates the maze in place, starting from `start_pos`.
+    ///
+    /// Without a start position, or with one that is not a tile of the maze, generation starts
+    /// from the origin, or the lowest tile for mazes that do not contain the origin. Using the
+    /// same seed will produce identical mazes. Without a seed, a random one is used.
     pub fn generate(&self, maze: &mut Maze, start_pos: Option<Hex>, seed: Option<u64>) {
+        if maze.is_empty() {
+            return;
+        }
+
+        let start = start_pos
+            .filter(|pos| maze.get(pos).is_some())
+            .unwrap_or_else(|| default_start(maze, maze.keys().copied()));
+        let mut rng = create_rng(seed);
+
+        MazeGenerator::generate(self, maze, start, &mut rng);
+    }
+
+    fn run(
+        self,
+        maze: &mut Maze,
+        start: Hex,
+        bias: DirectionBias,
+        rng: &mut dyn RngCore,
+        steps: &mut impl StepSink,
+    ) {
         match self {
-            Self::RecursiveBacktracking => generate_backtracking(maze, start_pos, seed),
+            Self::RecursiveBacktracking => {
+                generate_backtracking(maze, start, bias, &mut HashSet::new(), rng, steps);
+            }
+            Self::Prims => randomized_prims(maze, start, bias, rng, steps),
+            Self::Kruskal => randomized_kruskal(maze, rng, steps),
+            Self::Wilson => wilson(maze, start, rng, steps),
+            Self::AldousBroder => aldous_broder(maze, start, 100, rng, steps),
+            Self::AldousBroderWilson { switch_at } => {
+                aldous_broder(maze, start, switch_at, rng, steps);
+            }
+            Self::GrowingTree { policy } => growing_tree(maze, start, policy, bias, rng, steps),
+            Self::HuntAndKill => hunt_and_kill(maze, start, bias, rng, steps),
+            Self::RecursiveDivision => generate_recursive_division(maze, rng, steps),
+        }
+    }
+}
+
+impl MazeGenerator for GeneratorType {
+    fn generate(&self, maze: &mut Maze, start: Hex, rng: &mut dyn RngCore) {
+        self.run(maze, start, DirectionBias::Uniform, rng, &mut ());
+    }
+
+    fn generate_steps(
+        &self,
+        maze: &mut Maze,
+        start: Hex,
+        rng: &mut dyn RngCore,
+    ) -> Vec<GenerationStep> {
+        let mut steps = StepLog::default();
+        self.run(maze, start, DirectionBias::Uniform, rng, &mut steps);
+        steps.into_steps()
+    }
+}
+
+/// A built-in generator trying directions in the order weighted by `bias`.
+pub struct Biased {
+    pub generator: GeneratorType,
+    pub bias: DirectionBias,
+}
+
+impl MazeGenerator for Biased {
+    fn generate(&self, maze: &mut Maze, start: Hex, rng: &mut dyn RngCore) {
+        self.generator.run(maze, start, self.bias, rng, &mut ());
+    }
+
+    fn generate_steps(
+        &self,
+        maze: &mut Maze,
+        start: Hex,
+        rng: &mut dyn RngCore,
+    ) -> Vec<GenerationStep> {
+        let mut steps = StepLog::default();
+        self.generator.run(maze, start, self.bias, rng, &mut steps);
+        steps.into_steps()
+    }
+}
+
+/// Carves the passage from `from` into its neighbor in `direction`, recording the step.
+///
+/// Does nothing if there is no neighbor in that direction.
+fn carve<M: MazeStorage>(
+    maze: &mut M,
+    from: Hex,
+    direction: EdgeDirection,
+    steps: &mut impl StepSink,
+) {
+    let Some(to) = maze.neighbor(from, direction) else {
+        return;
+    };
+    let _ = maze.carve(&from, direction);
+    steps.record(GenerationStep {
+        to,
+        ..GenerationStep::new(from, direction)
+    });
+}
+
+/// Creates the random number generator used by the generators, seeded if a seed is given.
+pub fn create_rng(seed: Option<u64>) -> Box<dyn RngCore> {
+    seed.map_or_else(
+        || Box::new(thread_rng()) as Box<dyn RngCore>,
+        |seed| Box::new(StdRng::seed_from_u64(seed)) as Box<dyn RngCore>,
+    )
+}
+
+/// Returns the origin if it is a tile of the maze, or the lowest of `tiles` otherwise.
+///
+/// Used as the start position when none is given.
+pub fn default_start<M: MazeStorage>(maze: &M, tiles: impl Iterator<Item = Hex>) -> Hex {
+    if maze.get_walls(&Hex::ZERO).is_some() {
+        return Hex::ZERO;
+    }
+    tiles.min_by_key(|hex| (hex.x, hex.y)).unwrap_or(Hex::ZERO)
+}
+
+/// Returns `tiles` in a stable order, sorted by their coordinates.
+///
+/// Used wherever tiles are collected from a map or set, whose iteration order is not
+/// deterministic.
+pub fn sorted_tiles(tiles: impl IntoIterator<Item = Hex>) -> Vec<Hex> {
+    let mut tiles = tiles.into_iter().collect::<Vec<_>>();
+    tiles.sort_unstable_by_key(|hex| (hex.x, hex.y));
+    tiles
+}
+
+/// Returns the walls shared by each of `tiles` with a neighbor, as the tile, its neighbor and
+/// the direction between them.
+///
+/// The first three directions cover every axis once, so each shared wall is listed once.
+fn shared_walls<'a>(
+    maze: &'a Maze,
+    tiles: &'a [Hex],
+) -> impl Iterator<Item = (Hex, Hex, EdgeDirection)> + 'a {
+    tiles.iter().flat_map(move |&hex| {
+        EdgeDirection::ALL_DIRECTIONS[..3]
+            .iter()
+            .filter_map(move |&direction| Some((hex, maze.neighbor(hex, direction)?, direction)))
+    })
+}
+
+/// Returns the positions of all tiles reachable from `start` by moving between adjacent
+/// tiles, ignoring walls, in a stable order.
+///
+/// Used by random walk generators, which would never terminate on unreachable tiles.
+fn reachable_tiles(maze: &Maze, start: Hex) -> Vec<Hex> {
+    if maze.get(&start).is_none() {
+        return Vec::new();
+    }
+
+    let mut reachable = HashSet::from([start]);
+    let mut to_visit = vec![start];
+    while let Some(current) = to_visit.pop() {
+        for direction in EdgeDirection::ALL_DIRECTIONS {
+            if let Some(neighbor) = maze.neighbor(current, direction) {
+                if reachable.insert(neighbor) {
+                    to_visit.push(neighbor);
+                }
+            }
+        }
+    }
+
+    sorted_tiles(reachable)
+}
+
+/// Returns the tiles reachable from `start` through missing walls.
+#[cfg(test)]
+pub fn connected_tiles(maze: &Maze, start: Hex) -> HashSet<Hex> {
+    let mut connected = HashSet::from([start]);
+    let mut to_visit = vec![start];
+    while let Some(current) = to_visit.pop() {
+        let Some(walls) = maze.get_walls(&current) else {
+            continue;
+        };
+        for direction in EdgeDirection::ALL_DIRECTIONS {
+            if walls.contains(direction) {
+                continue;
+            }
+            if let Some(neighbor) = maze.neighbor(current, direction) {
+                if connected.insert(neighbor) {
+                    to_visit.push(neighbor);
+                }
+            }
+        }
+    }
+    connected
+}
+
+/// Returns the number of passages between tiles of the maze, counting each one once.
+#[cfg(test)]
+pub fn open_passages(maze: &Maze) -> usize {
+    maze.iter()
+        .map(|(&pos, tile)| {
+            EdgeDirection::ALL_DIRECTIONS
+                .into_iter()
+                .filter(|&direction| {
+                    !tile.walls.contains(direction) && maze.neighbor(pos, direction).is_some()
+                })
+                .count()
+        })
+        .sum::<usize>()
+        / 2
+}
+
+/// Splits `tiles` of the maze into connected components, where adjacent tiles belong to the
+/// same component only if `side` returns the same value for both.
+pub fn components(maze: &Maze, tiles: &[Hex], side: impl Fn(Hex) -> bool) -> Vec<Vec<Hex>> {
+    let in_tiles = tiles.iter().copied().collect::<HashSet<_>>();
+    let mut seen = HashSet::new();
+    let mut components = Vec::new();
+
+    for &tile in tiles {
+        if !seen.insert(tile) {
+            continue;
+        }
+        let mut component = vec![tile];
+        let mut to_visit = vec![tile];
+        while let Some(current) = to_visit.pop() {
+            for neighbor in EdgeDirection::ALL_DIRECTIONS
+                .into_iter()
+                .filter_map(|direction| maze.neighbor(current, direction))
+            {
+                if in_tiles.contains(&neighbor)
+                    && side(neighbor) == side(current)
+                    && seen.insert(neighbor)
+                {
+                    component.push(neighbor);
+                    to_visit.push(neighbor);
+                }
+            }
         }
+        components.push(component);
     }
+
+    components
 }
@@ -0,0 +1,137 @@
+use super::{carve, steps::StepSink};
+use crate::{DirectionBias, Maze};
+use hexx::{EdgeDirection, Hex};
+use rand::{seq::SliceRandom, Rng};
+use std::collections::{HashMap, HashSet};
+
+pub(super) fn randomized_prims<R: Rng + ?Sized>(
+    maze: &mut Maze,
+    start: Hex,
+    bias: DirectionBias,
+    rng: &mut R,
+    steps: &mut impl StepSink,
+) {
+    let mut visited = HashSet::from([start]);
+    // The direction each visited tile was entered in
+    let mut entered = HashMap::new();
+    let mut frontier = Vec::new();
+    let mut in_frontier = HashSet::new();
+    add_frontier(maze, start, &visited, &mut frontier, &mut in_frontier);
+
+    while !frontier.is_empty() {
+        let current = frontier.swap_remove(rng.gen_range(0..frontier.len()));
+        in_frontier.remove(&current);
+
+        // Connect the frontier tile to a random tile that is already part of the maze
+        let candidates = EdgeDirection::ALL_DIRECTIONS
+            .into_iter()
+            .filter_map(|direction| Some((maze.neighbor(current, direction)?, direction)))
+            .filter(|(neighbor, _)| visited.contains(neighbor))
+            .collect::<Vec<_>>();
+        let chosen = if bias == DirectionBias::Uniform {
+            candidates.choose(rng)
+        } else {
+            candidates
+                .choose_weighted(rng, |&(neighbor, direction)| {
+                    bias.weight(direction.const_neg(), entered.get(&neighbor).copied())
+                })
+                .ok()
+                // Every candidate weighs nothing, fall back to an unbiased choice
+                .or_else(|| candidates.choose(rng))
+        };
+        if let Some(&(neighbor, direction)) = chosen {
+            carve(maze, neighbor, direction.const_neg(), steps);
+            entered.insert(current, direction.const_neg());
+        }
+
+        visited.insert(current);
+        add_frontier(maze, current, &visited, &mut frontier, &mut in_frontier);
+    }
+}
+
+/// Adds every existing, not yet visited neighbor of `current` to the frontier.
+fn add_frontier(
+    maze: &Maze,
+    current: Hex,
+    visited: &HashSet<Hex>,
+    frontier: &mut Vec<Hex>,
+    in_frontier: &mut HashSet<Hex>,
+) {
+    for direction in EdgeDirection::ALL_DIRECTIONS {
+        let Some(neighbor) = maze.neighbor(current, direction) else {
+            continue;
+        };
+        if !visited.contains(&neighbor) && in_frontier.insert(neighbor) {
+            frontier.push(neighbor);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{builder::create_hex_maze, generator::connected_tiles};
+    use claims::assert_some;
+    use rand::{rngs::StdRng, SeedableRng};
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(Hex::ZERO)]
+    #[case(Hex::new(1, -1))]
+    #[case(Hex::new(-2, 2))]
+    fn randomized_prims_walls_removed(#[case] start: Hex) {
+        let mut maze = create_hex_maze(3);
+        let mut rng = StdRng::seed_from_u64(12345);
+
+        randomized_prims(&mut maze, start, DirectionBias::Uniform, &mut rng, &mut ());
+
+        for &pos in maze.keys() {
+            let walls = assert_some!(maze.get_walls(&pos));
+            assert!(
+                walls.count() < 6,
+                "At least one wall should be removed for each tile"
+            );
+        }
+    }
+
+    #[rstest]
+    #[case(Hex::ZERO)]
+    #[case(Hex::new(1, -1))]
+    #[case(Hex::new(-2, 2))]
+    fn randomized_prims_connectivity(#[case] start: Hex) {
+        let mut maze = create_hex_maze(3);
+        let mut rng = StdRng::seed_from_u64(12345);
+
+        randomized_prims(&mut maze, start, DirectionBias::Uniform, &mut rng, &mut ());
+
+        let connected = connected_tiles(&maze, start);
+        assert_eq!(
+            connected.len(),
+            maze.count(),
+            "All tiles should be connected"
+        );
+    }
+
+    #[test]
+    fn randomized_prims_deterministic() {
+        let mut maze1 = create_hex_maze(5);
+        let mut maze2 = create_hex_maze(5);
+
+        randomized_prims(
+            &mut maze1,
+            Hex::ZERO,
+            DirectionBias::Uniform,
+            &mut StdRng::seed_from_u64(12345),
+            &mut (),
+        );
+        randomized_prims(
+            &mut maze2,
+            Hex::ZERO,
+            DirectionBias::Uniform,
+            &mut StdRng::seed_from_u64(12345),
+            &mut (),
+        );
+
+        assert_eq!(maze1, maze2, "Same seed should produce identical mazes");
+    }
+}
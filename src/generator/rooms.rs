@@ -0,0 +1,216 @@
+use super::{
+    carve, components, disjoint_set::DisjointSet, shared_walls, sorted_tiles, steps::StepSink,
+};
+use crate::{Maze, MazeGenerator};
+use hexx::{EdgeDirection, Hex};
+use rand::{seq::SliceRandom, RngCore};
+use std::collections::HashMap;
+
+/// Generates a maze around open rooms, connecting each room through doorways.
+///
+/// Every room is given as its tiles and the number of doorways it should have. Tiles
+/// belonging to several rooms are part of the first one, and tiles outside the maze are
+/// ignored. The corridors between the rooms are generated with `generator`, separately for
+/// each region the rooms split them into.
+///
+/// The doorways are first picked to connect all rooms and regions, so a room that separates
+/// regions may get more doorways than requested, and every room gets at least one.
+pub fn generate_with_rooms(
+    maze: &mut Maze,
+    rooms: &[(Vec<Hex>, usize)],
+    generator: &dyn MazeGenerator,
+    start: Hex,
+    rng: &mut dyn RngCore,
+    steps: &mut impl StepSink,
+) {
+    let mut room_of = HashMap::new();
+    for (index, (tiles, _)) in rooms.iter().enumerate() {
+        for &hex in tiles {
+            if maze.get(&hex).is_some() {
+                room_of.entry(hex).or_insert(index);
+            }
+        }
+    }
+
+    // Open up the rooms
+    let tiles = sorted_tiles(maze.keys().copied());
+    let room_walls = shared_walls(maze, &tiles)
+        .filter(|(hex, neighbor, _)| {
+            room_of
+                .get(hex)
+                .is_some_and(|room| room_of.get(neighbor) == Some(room))
+        })
+        .collect::<Vec<_>>();
+    for (hex, _, direction) in room_walls {
+        carve(maze, hex, direction, steps);
+    }
+
+    // Generate the corridors of every region between the rooms on their own
+    let corridors = tiles
+        .iter()
+        .copied()
+        .filter(|hex| !room_of.contains_key(hex))
+        .collect::<Vec<_>>();
+    let regions = components(maze, &corridors, |_| true);
+    let mut node_of = room_of.clone();
+    for (index, region) in regions.iter().enumerate() {
+        node_of.extend(region.iter().map(|&hex| (hex, rooms.len() + index)));
+
+        let mut corridor = region.iter().copied().collect::<Maze>();
+        corridor.set_topology(maze.topology());
+        let region_start = if region.contains(&start) {
+            start
+        } else {
+            region[0]
+        };
+        for step in generator.generate_steps(&mut corridor, region_start, rng) {
+            step.apply(maze);
+            steps.record(step);
+        }
+    }
+
+    // Walls between a room and anything else, listed once from the room with the lowest index
+    let mut doorways = tiles
+        .iter()
+        .filter_map(|hex| room_of.get(hex).map(|&room| (*hex, room)))
+        .flat_map(|(hex, room)| {
+            EdgeDirection::ALL_DIRECTIONS
+                .into_iter()
+                .map(move |direction| (hex, room, direction))
+        })
+        .filter_map(|(hex, room, direction)| {
+            let neighbor = maze.neighbor(hex, direction)?;
+            room_of
+                .get(&neighbor)
+                .map_or_else(|| node_of.contains_key(&neighbor), |&other| other > room)
+                .then_some((hex, room, direction, neighbor))
+        })
+        .collect::<Vec<_>>();
+    doorways.shuffle(rng);
+
+    // Connect everything first, then add doorways to rooms that want more
+    let mut sets = DisjointSet::new(rooms.len() + regions.len());
+    let mut opened = doorways
+        .iter()
+        .map(|&(_, room, _, neighbor)| sets.union(room, node_of[&neighbor]))
+        .collect::<Vec<_>>();
+    let mut counts = vec![0; rooms.len()];
+    let count = |counts: &mut [usize], (_, room, _, neighbor): (Hex, usize, EdgeDirection, Hex)| {
+        counts[room] += 1;
+        if let Some(&other) = room_of.get(&neighbor) {
+            counts[other] += 1;
+        }
+    };
+    for (&doorway, _) in doorways.iter().zip(&opened).filter(|(_, &opened)| opened) {
+        count(&mut counts, doorway);
+    }
+    for (&doorway, opened) in doorways.iter().zip(&mut opened) {
+        let (_, room, _, neighbor) = doorway;
+        let wanted = |room: usize| counts[room] < rooms[room].1;
+        let other = room_of.get(&neighbor).copied();
+        if !*opened && (wanted(room) || other.is_some_and(wanted)) {
+            *opened = true;
+            count(&mut counts, doorway);
+        }
+    }
+
+    for (&(hex, _, direction, _), _) in doorways.iter().zip(opened).filter(|(_, opened)| *opened) {
+        carve(maze, hex, direction, steps);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{builder::create_hex_maze, generator::connected_tiles, GeneratorType};
+    use claims::assert_some;
+    use rand::{rngs::StdRng, SeedableRng};
+    use rstest::rstest;
+
+    /// Returns the number of open walls between `room` and the tiles around it.
+    fn doorways(maze: &Maze, room: &[Hex]) -> usize {
+        room.iter()
+            .flat_map(|&hex| {
+                EdgeDirection::ALL_DIRECTIONS
+                    .into_iter()
+                    .map(move |direction| (hex, direction))
+            })
+            .filter(|&(hex, direction)| {
+                let neighbor = hex + direction;
+                !room.contains(&neighbor)
+                    && maze.get(&neighbor).is_some()
+                    && maze
+                        .get_walls(&hex)
+                        .is_some_and(|walls| !walls.contains(direction))
+            })
+            .count()
+    }
+
+    #[rstest]
+    #[case(1)]
+    #[case(2)]
+    #[case(4)]
+    fn room_has_requested_doorways(#[case] count: usize) {
+        let mut maze = create_hex_maze(6);
+        let room = Hex::new(2, -1).range(1).collect::<Vec<_>>();
+        let mut rng = StdRng::seed_from_u64(12345);
+
+        generate_with_rooms(
+            &mut maze,
+            &[(room.clone(), count)],
+            &GeneratorType::RecursiveBacktracking,
+            Hex::ZERO,
+            &mut rng,
+            &mut (),
+        );
+
+        assert_eq!(doorways(&maze, &room), count);
+        assert_eq!(connected_tiles(&maze, Hex::ZERO).len(), maze.count());
+        for &hex in &room {
+            for direction in EdgeDirection::ALL_DIRECTIONS {
+                if room.contains(&(hex + direction)) {
+                    assert!(!assert_some!(maze.get_walls(&hex)).contains(direction));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn room_splitting_corridors_stays_connected() {
+        // A room spanning the whole maze from edge to edge, with no doorways requested
+        let mut maze = create_hex_maze(4);
+        let room = (-4..=4).map(|x| Hex::new(x, 0)).collect::<Vec<_>>();
+        let mut rng = StdRng::seed_from_u64(12345);
+
+        generate_with_rooms(
+            &mut maze,
+            &[(room.clone(), 0)],
+            &GeneratorType::Prims,
+            Hex::ZERO,
+            &mut rng,
+            &mut (),
+        );
+
+        assert_eq!(doorways(&maze, &room), 2);
+        assert_eq!(connected_tiles(&maze, Hex::ZERO).len(), maze.count());
+    }
+
+    #[test]
+    fn adjacent_rooms_stay_connected() {
+        let mut maze = create_hex_maze(5);
+        let first = Hex::new(-2, 0).range(1).collect::<Vec<_>>();
+        let second = Hex::new(1, 0).range(1).collect::<Vec<_>>();
+        let mut rng = StdRng::seed_from_u64(12345);
+
+        generate_with_rooms(
+            &mut maze,
+            &[(first, 1), (second, 1)],
+            &GeneratorType::Kruskal,
+            Hex::new(4, 0),
+            &mut rng,
+            &mut (),
+        );
+
+        assert_eq!(connected_tiles(&maze, Hex::new(4, 0)).len(), maze.count());
+    }
+}
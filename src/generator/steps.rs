@@ -0,0 +1,358 @@
+use super::sorted_tiles;
+use crate::Maze;
+use hexx::{EdgeDirection, Hex};
+use std::collections::{HashMap, HashSet};
+
+/// A single passage carved during maze generation.
+///
+/// Carving removes the wall of `from` in `direction` and the opposite wall of `to`. Passages
+/// tunneling under a tile in weave mazes connect the tiles on either side of it, leaving the
+/// walls of the tile in between untouched.
+#[allow(clippy::module_name_repetitions)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "bevy_reflect", derive(bevy_reflect::Reflect))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct GenerationStep {
+    /// The tile the passage is carved from.
+    pub from: Hex,
+    /// The tile the passage is carved into.
+    pub to: Hex,
+    /// The direction from `from` towards `to`.
+    pub direction: EdgeDirection,
+    /// The tile the passage tunnels under, if any.
+    pub under: Option<Hex>,
+}
+
+impl GenerationStep {
+    /// Creates a step carving from `from` into its neighbor in `direction`.
+    ///
+    /// The neighbor is the adjacent position, without wrapping around the edges of a maze, see
+    /// [`Maze::neighbor`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use hexlab::prelude::*;
+    ///
+    /// let step = GenerationStep::new(Hex::ZERO, EdgeDirection::FLAT_NORTH);
+    /// assert_eq!(step.to, Hex::ZERO + EdgeDirection::FLAT_NORTH);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn new(from: Hex, direction: EdgeDirection) -> Self {
+        Self {
+            from,
+            to: from + direction,
+            direction,
+            under: None,
+        }
+    }
+
+    /// Creates a step tunneling from `from` under its neighbor `under` in `direction`, into
+    /// the tile beyond it.
+    #[inline]
+    #[must_use]
+    pub(crate) const fn tunnel(from: Hex, under: Hex, to: Hex, direction: EdgeDirection) -> Self {
+        Self {
+            from,
+            to,
+            direction,
+            under: Some(under),
+        }
+    }
+
+    /// Carves the passage described by this step into `maze`.
+    pub(crate) fn apply(self, maze: &mut Maze) {
+        let Some(under) = self.under else {
+            let _ = maze.carve(&self.from, self.direction);
+            return;
+        };
+        // The tunnel ends face the crossed tile, which keeps its own walls
+        let _ = maze.remove_tile_wall(&self.from, self.direction);
+        let _ = maze.remove_tile_wall(&self.to, self.direction.const_neg());
+        if let Some(tile) = maze.get_mut(&under) {
+            tile.set_under_passage(Some(self.direction));
+        }
+    }
+}
+
+/// Receives the steps performed by a generator.
+pub trait StepSink {
+    fn record(&mut self, step: GenerationStep);
+
+    /// Forgets the step carving the passage between `a` and `b`, used when a later pass walls
+    /// it up again.
+    fn retract(&mut self, a: Hex, b: Hex);
+}
+
+/// Discards all steps, used when only the final maze is of interest.
+impl StepSink for () {
+    #[inline]
+    fn record(&mut self, _step: GenerationStep) {}
+
+    #[inline]
+    fn retract(&mut self, _a: Hex, _b: Hex) {}
+}
+
+/// Records the steps performed by a generator in order.
+///
+/// Plain passages are indexed by their tiles, so retracting one does not scan the log.
+#[derive(Debug, Default)]
+pub struct StepLog {
+    steps: Vec<Option<GenerationStep>>,
+    passages: HashMap<(Hex, Hex), usize>,
+}
+
+impl StepLog {
+    /// Returns the steps that were recorded and not retracted, in order.
+    pub fn into_steps(self) -> Vec<GenerationStep> {
+        self.steps.into_iter().flatten().collect()
+    }
+}
+
+/// Returns the key of the passage between `a` and `b`, independent of their order.
+fn passage(a: Hex, b: Hex) -> (Hex, Hex) {
+    if (a.x, a.y) <= (b.x, b.y) {
+        (a, b)
+    } else {
+        (b, a)
+    }
+}
+
+impl StepSink for StepLog {
+    fn record(&mut self, step: GenerationStep) {
+        if step.under.is_none() {
+            self.passages
+                .insert(passage(step.from, step.to), self.steps.len());
+        }
+        self.steps.push(Some(step));
+    }
+
+    fn retract(&mut self, a: Hex, b: Hex) {
+        if let Some(index) = self.passages.remove(&passage(a, b)) {
+            self.steps[index] = None;
+        }
+    }
+}
+
+/// Returns the steps carving `after` from `before`, in a stable tile order.
+///
+/// Every removed wall shared by two tiles is reported once, from the tile with the lower
+/// coordinates.
+pub fn diff_steps(before: &Maze, after: &Maze) -> Vec<GenerationStep> {
+    sorted_tiles(after.keys().copied())
+        .into_iter()
+        .flat_map(|pos| {
+            EdgeDirection::ALL_DIRECTIONS
+                .into_iter()
+                .map(move |direction| {
+                    after.neighbor(pos, direction).map_or_else(
+                        || GenerationStep::new(pos, direction),
+                        |to| GenerationStep {
+                            to,
+                            ..GenerationStep::new(pos, direction)
+                        },
+                    )
+                })
+        })
+        .filter(|step| {
+            let removed = |pos: &Hex, direction| {
+                before
+                    .get_walls(pos)
+                    .is_some_and(|walls| walls.contains(direction))
+                    && after
+                        .get_walls(pos)
+                        .is_some_and(|walls| !walls.contains(direction))
+            };
+            let reverse_removed = removed(&step.to, step.direction.const_neg());
+            removed(&step.from, step.direction)
+                && (!reverse_removed || (step.from.x, step.from.y) < (step.to.x, step.to.y))
+        })
+        .collect()
+}
+
+/// An iterator replaying the steps of a maze generation one at a time.
+///
+/// Created by [`MazeBuilder::build_steps`]. Each call to [`Iterator::next`] carves a single
+/// passage into the maze, which can be inspected in between with [`GenerationSteps::maze`],
+/// for example to animate the generation. Once drained, the maze is identical to the one
+/// returned by [`MazeBuilder::build`] with the same configuration and seed.
+///
+/// This is a replay, not a live generation: the generator runs to completion when the iterator
+/// is created and records every step, so creating it costs as much time as
+/// [`MazeBuilder::build`] plus memory for all steps. Iterating then only re-applies the
+/// recorded steps to a fully walled copy of the maze, so it can be paused and resumed at any
+/// point without affecting the result.
+///
+/// Steps of custom generators that do not override [`MazeGenerator::generate_steps`] are
+/// replayed in tile order rather than in the order they were carved.
+///
+/// [`MazeGenerator::generate_steps`]: crate::MazeGenerator::generate_steps
+/// [`MazeBuilder::build_steps`]: crate::MazeBuilder::build_steps
+/// [`MazeBuilder::build`]: crate::MazeBuilder::build
+///
+/// # Examples
+///
+/// ```
+/// use hexlab::prelude::*;
+///
+/// let mut steps = MazeBuilder::new()
+///     .with_radius(3)
+///     .with_seed(12345)
+///     .build_steps()
+///     .expect("Failed to create maze");
+///
+/// let step = steps.next().expect("Maze should have at least one step");
+/// assert!(!steps.maze().get_walls(&step.from).unwrap().contains(step.direction));
+/// assert!(steps.visited().contains(&step.to));
+///
+/// let maze = MazeBuilder::new()
+///     .with_radius(3)
+///     .with_seed(12345)
+///     .build()
+///     .expect("Failed to create maze");
+/// assert_eq!(steps.into_maze(), maze);
+/// ```
+#[derive(Debug, Clone)]
+pub struct GenerationSteps {
+    maze: Maze,
+    steps: std::vec::IntoIter<GenerationStep>,
+    visited: HashSet<Hex>,
+    frontier: HashSet<Hex>,
+}
+
+impl GenerationSteps {
+    pub(crate) fn new(maze: Maze, steps: Vec<GenerationStep>, start: Hex) -> Self {
+        let mut generation = Self {
+            maze,
+            steps: steps.into_iter(),
+            visited: HashSet::new(),
+            frontier: HashSet::new(),
+        };
+        generation.visit(start);
+        generation
+    }
+
+    /// Returns the maze as carved so far.
+    #[inline]
+    #[must_use]
+    pub const fn maze(&self) -> &Maze {
+        &self.maze
+    }
+
+    /// Returns the tiles connected to the maze so far.
+    #[inline]
+    #[must_use]
+    pub const fn visited(&self) -> &HashSet<Hex> {
+        &self.visited
+    }
+
+    /// Returns the tiles that are not yet visited, but are next to a visited tile.
+    #[inline]
+    #[must_use]
+    pub const fn frontier(&self) -> &HashSet<Hex> {
+        &self.frontier
+    }
+
+    /// Returns the number of steps left.
+    #[inline]
+    #[must_use]
+    pub fn remaining(&self) -> usize {
+        self.steps.len()
+    }
+
+    /// Performs all remaining steps and returns the finished maze.
+    #[must_use]
+    pub fn into_maze(mut self) -> Maze {
+        self.by_ref().for_each(drop);
+        self.maze
+    }
+
+    fn visit(&mut self, pos: Hex) {
+        if self.maze.get(&pos).is_none() || !self.visited.insert(pos) {
+            return;
+        }
+        self.frontier.remove(&pos);
+        for direction in EdgeDirection::ALL_DIRECTIONS {
+            if let Some(neighbor) = self.maze.neighbor(pos, direction) {
+                if !self.visited.contains(&neighbor) {
+                    self.frontier.insert(neighbor);
+                }
+            }
+        }
+    }
+}
+
+impl Iterator for GenerationSteps {
+    type Item = GenerationStep;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let step = self.steps.next()?;
+        step.apply(&mut self.maze);
+        self.visit(step.from);
+        self.visit(step.to);
+        Some(step)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.steps.size_hint()
+    }
+}
+
+impl ExactSizeIterator for GenerationSteps {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::builder::create_hex_maze;
+
+    #[test]
+    fn diff_steps_reports_shared_walls_once() {
+        let before = create_hex_maze(1);
+        let mut after = before.clone();
+        GenerationStep::new(Hex::ZERO, EdgeDirection::FLAT_NORTH).apply(&mut after);
+        GenerationStep::new(Hex::ZERO, EdgeDirection::FLAT_SOUTH).apply(&mut after);
+
+        let steps = diff_steps(&before, &after);
+
+        assert_eq!(steps.len(), 2);
+        let mut replayed = before;
+        for step in steps {
+            step.apply(&mut replayed);
+        }
+        assert_eq!(replayed, after);
+    }
+
+    #[test]
+    fn step_log_retract() {
+        let north = GenerationStep::new(Hex::ZERO, EdgeDirection::FLAT_NORTH);
+        let south = GenerationStep::new(Hex::ZERO, EdgeDirection::FLAT_SOUTH);
+        let tunnel = GenerationStep::tunnel(south.to, Hex::ZERO, north.to, north.direction);
+        let mut log = StepLog::default();
+        log.record(north);
+        log.record(south);
+        log.record(tunnel);
+
+        // Retracting works from either side and leaves tunnels between the same tiles alone
+        log.retract(north.to, Hex::ZERO);
+        log.retract(south.to, north.to);
+
+        assert_eq!(log.into_steps(), vec![south, tunnel]);
+    }
+
+    #[test]
+    fn generation_steps_frontier() {
+        let maze = create_hex_maze(2);
+        let step = GenerationStep::new(Hex::ZERO, EdgeDirection::FLAT_NORTH);
+        let mut steps = GenerationSteps::new(maze, vec![step], Hex::ZERO);
+
+        assert_eq!(steps.visited().len(), 1);
+        assert_eq!(steps.frontier().len(), 6);
+
+        assert_eq!(steps.next(), Some(step));
+        assert_eq!(steps.visited().len(), 2);
+        // The ring around the origin, plus the three new tiles next to the carved one
+        assert_eq!(steps.frontier().len(), 8);
+        assert_eq!(steps.remaining(), 0);
+    }
+}
@@ -0,0 +1,297 @@
+use super::{sorted_tiles, steps::StepSink};
+use crate::{GenerationStep, Maze};
+use hexx::{EdgeDirection, Hex};
+use rand::{seq::SliceRandom, Rng};
+use std::collections::{HashMap, HashSet, VecDeque};
+
+/// Turns `fraction` of the straight corridors, in random order, into crossings with a passage
+/// tunneling under them.
+///
+/// Each tunnel joins the two tiles on either side of the corridor, which are already connected
+/// elsewhere in the maze. To keep a perfect maze perfect, a random passage on that other route
+/// is walled up again. Routes are looked up in a spanning tree of the passages, built once and
+/// updated with every crossing.
+#[allow(
+    clippy::cast_possible_truncation,
+    clippy::cast_sign_loss,
+    clippy::cast_precision_loss
+)]
+pub fn weave<R: Rng + ?Sized>(
+    maze: &mut Maze,
+    fraction: f32,
+    rng: &mut R,
+    steps: &mut impl StepSink,
+) {
+    let mut corridors = sorted_tiles(maze.keys().copied())
+        .into_iter()
+        .filter(|&pos| crossings(maze, pos).next().is_some())
+        .collect::<Vec<_>>();
+    corridors.shuffle(rng);
+    let count = (fraction.clamp(0.0, 1.0) * corridors.len() as f32).round() as usize;
+
+    let mut tree = Tree::new(maze);
+    for pos in corridors.into_iter().take(count) {
+        // Earlier crossings may have changed the corridor or its neighbors
+        let candidates = crossings(maze, pos).collect::<Vec<_>>();
+        let Some(&(from, direction, to)) = candidates.choose(rng) else {
+            continue;
+        };
+
+        // Passages on the route the tunnel replaces, away from any crossing
+        let Some(route) = tree.route(from, to) else {
+            continue;
+        };
+        let plain =
+            |hex: Hex| hex != pos && maze.get(&hex).is_some_and(|tile| tile.under.is_none());
+        let cuttable = route
+            .iter()
+            .enumerate()
+            .flat_map(|(side, path)| (1..path.len()).map(move |length| (side, length)))
+            .filter(|&(side, length)| {
+                let (child, parent) = (route[side][length - 1], route[side][length]);
+                !tree.is_tunnel(child) && plain(child) && plain(parent)
+            })
+            .collect::<Vec<_>>();
+        let Some(&(side, length)) = cuttable.choose(rng) else {
+            continue;
+        };
+
+        // The tiles walked up from one end of the tunnel to the cut passage now hang from the
+        // other end, through the tunnel
+        let (child, parent) = (route[side][length - 1], route[side][length]);
+        wall_up(maze, child, parent);
+        steps.retract(child, parent);
+        tree.reroot(&route[side][..length], if side == 0 { to } else { from });
+        let step = GenerationStep::tunnel(from, pos, to, direction);
+        step.apply(maze);
+        steps.record(step);
+    }
+}
+
+/// A spanning tree of the passages of a maze, as the parent of every tile but the roots.
+///
+/// Finding the route between two tiles only walks up from both of them, rather than searching
+/// the whole maze for every crossing.
+struct Tree {
+    /// The parent of each tile, and whether they are joined by a tunnel.
+    parents: HashMap<Hex, (Hex, bool)>,
+}
+
+impl Tree {
+    /// Builds a tree of the passages and tunnels of the maze, with one root per connected part.
+    fn new(maze: &Maze) -> Self {
+        let mut parents = HashMap::new();
+        let mut visited = HashSet::new();
+        for root in sorted_tiles(maze.keys().copied()) {
+            if !visited.insert(root) {
+                continue;
+            }
+            let mut to_visit = VecDeque::from([root]);
+            while let Some(current) = to_visit.pop_front() {
+                for (next, tunnel) in links(maze, current) {
+                    if visited.insert(next) {
+                        parents.insert(next, (current, tunnel));
+                        to_visit.push_back(next);
+                    }
+                }
+            }
+        }
+        Self { parents }
+    }
+
+    /// Returns `true` if the tile is joined to its parent by a tunnel.
+    fn is_tunnel(&self, hex: Hex) -> bool {
+        self.parents.get(&hex).is_some_and(|&(_, tunnel)| tunnel)
+    }
+
+    /// Returns the route between two tiles, as the tiles walked up from `from` and from `to`
+    /// until both reach the tile where they meet, or [`None`] if they are not connected.
+    ///
+    /// Both ends take turns, so neither walks much further than the route is long.
+    fn route(&self, from: Hex, to: Hex) -> Option<[Vec<Hex>; 2]> {
+        let mut paths = [vec![from], vec![to]];
+        let mut seen = [HashMap::from([(from, 0)]), HashMap::from([(to, 0)])];
+        loop {
+            let mut walked = false;
+            for side in 0..2 {
+                let last = paths[side][paths[side].len() - 1];
+                let Some(&(parent, _)) = self.parents.get(&last) else {
+                    continue;
+                };
+                walked = true;
+                paths[side].push(parent);
+                if let Some(&index) = seen[1 - side].get(&parent) {
+                    paths[1 - side].truncate(index + 1);
+                    return Some(paths);
+                }
+                seen[side].insert(parent, paths[side].len() - 1);
+            }
+            if !walked {
+                return None;
+            }
+        }
+    }
+
+    /// Replaces the passage above the last of `path` with a tunnel from the first of `path`
+    /// to `end`, turning the tiles of `path` upside down so the cut off part hangs from `end`.
+    fn reroot(&mut self, path: &[Hex], end: Hex) {
+        let mut parent = (end, true);
+        for &hex in path {
+            let previous = self.parents.insert(hex, parent);
+            parent = (hex, previous.is_some_and(|(_, tunnel)| tunnel));
+        }
+    }
+}
+
+/// Returns the tiles joined to the tile at `pos` by a passage, or by a tunnel under a
+/// neighbor, see [`Maze::moves`].
+fn links(maze: &Maze, pos: Hex) -> impl Iterator<Item = (Hex, bool)> + '_ {
+    maze.moves(pos, None)
+        .into_iter()
+        .filter_map(|(next, under)| match under {
+            None => Some((next, false)),
+            Some(_) => maze
+                .moves(next, under)
+                .into_iter()
+                .find(|(_, under)| under.is_none())
+                .map(|(end, _)| (end, true)),
+        })
+}
+
+/// Returns the ways a passage can tunnel under the tile at `pos`, as the tile it starts from,
+/// its direction and the tile it ends in.
+///
+/// The tile must be a straight corridor without an under-passage, crossed along another axis
+/// by a passage between two walled off neighbors without under-passages of their own.
+fn crossings(maze: &Maze, pos: Hex) -> impl Iterator<Item = (Hex, EdgeDirection, Hex)> + '_ {
+    let tile = maze.get(&pos).filter(|tile| tile.under.is_none());
+    let open = tile.map_or_else(Vec::new, |tile| {
+        EdgeDirection::ALL_DIRECTIONS
+            .into_iter()
+            .filter(|&direction| !tile.walls.contains(direction))
+            .collect::<Vec<_>>()
+    });
+    let straight = matches!(*open, [a, b] if a == b.const_neg());
+
+    EdgeDirection::ALL_DIRECTIONS
+        .into_iter()
+        .filter(move |&direction| straight && !open.contains(&direction))
+        .filter_map(move |direction| {
+            let from = maze.neighbor(pos, direction.const_neg())?;
+            let to = maze.neighbor(pos, direction)?;
+            let walled = |hex: Hex, direction| {
+                maze.get(&hex)
+                    .is_some_and(|tile| tile.under.is_none() && tile.walls.contains(direction))
+            };
+            (from != to && walled(from, direction) && walled(to, direction.const_neg()))
+                .then_some((from, direction, to))
+        })
+}
+
+/// Walls up the passage between the adjacent tiles `a` and `b`.
+fn wall_up(maze: &mut Maze, a: Hex, b: Hex) {
+    if let Some(direction) = EdgeDirection::ALL_DIRECTIONS
+        .into_iter()
+        .find(|&direction| maze.neighbor(a, direction) == Some(b))
+    {
+        let _ = maze.wall_up(&a, direction);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{builder::create_hex_maze, generator::StepLog, GeneratorType};
+    use claims::assert_some;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    fn woven_maze() -> (Maze, Vec<GenerationStep>) {
+        let mut maze = create_hex_maze(8);
+        GeneratorType::RecursiveBacktracking.generate(&mut maze, None, Some(12345));
+        let mut log = StepLog::default();
+        weave(&mut maze, 1.0, &mut StdRng::seed_from_u64(12345), &mut log);
+        let steps = log.into_steps();
+        (maze, steps)
+    }
+
+    #[test]
+    fn weave_creates_crossings() {
+        let (maze, steps) = woven_maze();
+
+        let tunnels = steps
+            .iter()
+            .filter_map(|step| Some((step.under?, step)))
+            .collect::<Vec<_>>();
+        assert!(!tunnels.is_empty());
+        for (under, step) in tunnels {
+            let tile = assert_some!(maze.get(&under));
+            assert!(tile.has_under_passage(step.direction));
+            // The walls of the crossed tile belong to the passage on top
+            assert!(tile.walls.contains(step.direction));
+            assert!(tile.walls.contains(step.direction.const_neg()));
+            assert!(!assert_some!(maze.get_walls(&step.from)).contains(step.direction));
+            assert!(!assert_some!(maze.get_walls(&step.to)).contains(step.direction.const_neg()));
+        }
+    }
+
+    #[test]
+    fn weave_keeps_maze_perfect() {
+        let (maze, _) = woven_maze();
+
+        // Every tile is reached on top exactly once, through a single route
+        let mut seen = HashSet::from([(Hex::ZERO, None)]);
+        let mut to_visit = vec![(Hex::ZERO, None)];
+        let mut moves = 0;
+        while let Some((pos, under)) = to_visit.pop() {
+            for next in maze.moves(pos, under) {
+                moves += 1;
+                if seen.insert(next) {
+                    to_visit.push(next);
+                }
+            }
+        }
+        let on_top = seen.iter().filter(|(_, under)| under.is_none()).count();
+        assert_eq!(on_top, maze.count());
+
+        // Each tunnel is passed through one position underneath per direction, entered and left
+        // once each, while passages on top are counted from both sides
+        let underneath = seen.len() - on_top;
+        let passages = (moves - 2 * underneath) / 2 + underneath / 2;
+        assert_eq!(passages, maze.count() - 1, "Maze should be a spanning tree");
+    }
+
+    /// Returns a corridor of five tiles along the x axis, and a tile on its own.
+    fn corridor() -> Maze {
+        let mut maze = (-2..=2)
+            .map(|x| Hex::new(x, 0))
+            .chain([Hex::new(5, 5)])
+            .collect::<Maze>();
+        maze.open_shared_walls();
+        maze
+    }
+
+    #[test]
+    fn tree_route_meets_at_common_ancestor() {
+        let tree = Tree::new(&corridor());
+
+        let [from, to] = assert_some!(tree.route(Hex::new(0, 0), Hex::new(2, 0)));
+        assert_eq!(from, vec![Hex::new(0, 0)]);
+        assert_eq!(to, vec![Hex::new(2, 0), Hex::new(1, 0), Hex::new(0, 0)]);
+        assert!(tree.route(Hex::new(0, 0), Hex::new(5, 5)).is_none());
+    }
+
+    #[test]
+    fn tree_reroot_hangs_cut_off_tiles_from_tunnel() {
+        let mut tree = Tree::new(&corridor());
+
+        // Cut the passage between the center and its eastern neighbor, tunneling from the
+        // eastern end to the western end instead
+        tree.reroot(&[Hex::new(2, 0), Hex::new(1, 0)], Hex::new(-2, 0));
+
+        assert!(tree.is_tunnel(Hex::new(2, 0)));
+        assert!(!tree.is_tunnel(Hex::new(1, 0)));
+        let [from, to] = assert_some!(tree.route(Hex::new(1, 0), Hex::new(0, 0)));
+        assert_eq!(from, vec![Hex::new(1, 0), Hex::new(2, 0), Hex::new(-2, 0)]);
+        assert_eq!(to, vec![Hex::new(0, 0), Hex::new(-1, 0), Hex::new(-2, 0)]);
+    }
+}
@@ -0,0 +1,129 @@
+use super::{carve, reachable_tiles, steps::StepSink};
+use crate::Maze;
+use hexx::{EdgeDirection, Hex};
+use rand::{seq::SliceRandom, Rng};
+use std::collections::{HashMap, HashSet};
+
+pub(super) fn wilson<R: Rng + ?Sized>(
+    maze: &mut Maze,
+    start: Hex,
+    rng: &mut R,
+    steps: &mut impl StepSink,
+) {
+    let tiles = reachable_tiles(maze, start);
+    let mut in_tree = HashSet::from([start]);
+
+    loop_erased_walks(maze, &tiles, &mut in_tree, rng, steps);
+}
+
+/// Adds every tile in `tiles` that is not yet part of the tree using loop-erased random walks.
+///
+/// `in_tree` must contain at least one tile, otherwise the walks never terminate.
+pub(super) fn loop_erased_walks<R: Rng + ?Sized>(
+    maze: &mut Maze,
+    tiles: &[Hex],
+    in_tree: &mut HashSet<Hex>,
+    rng: &mut R,
+    steps: &mut impl StepSink,
+) {
+    let mut exits = HashMap::new();
+
+    for &tile in tiles {
+        if in_tree.contains(&tile) {
+            continue;
+        }
+
+        // Random walk until the tree is hit. Only the last exit taken from each tile is
+        // remembered, which erases any loops the walk made along the way.
+        let mut current = tile;
+        while !in_tree.contains(&current) {
+            let Some(direction) = random_neighbor_direction(maze, current, rng) else {
+                return;
+            };
+            exits.insert(current, direction);
+            current = maze.neighbor(current, direction).unwrap_or(current);
+        }
+
+        // Follow the loop-erased path and add it to the tree
+        let mut current = tile;
+        while !in_tree.contains(&current) {
+            let direction = exits[&current];
+            let Some(neighbor) = maze.neighbor(current, direction) else {
+                return;
+            };
+            carve(maze, current, direction, steps);
+            in_tree.insert(current);
+            current = neighbor;
+        }
+    }
+}
+
+/// Picks a uniformly random direction leading to an existing neighbor of `current`.
+///
+/// Returns `None` if the tile has no neighbors.
+pub(super) fn random_neighbor_direction<R: Rng + ?Sized>(
+    maze: &Maze,
+    current: Hex,
+    rng: &mut R,
+) -> Option<EdgeDirection> {
+    let directions = EdgeDirection::ALL_DIRECTIONS
+        .into_iter()
+        .filter(|&direction| maze.neighbor(current, direction).is_some())
+        .collect::<Vec<_>>();
+    directions.choose(rng).copied()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        builder::create_hex_maze,
+        generator::{connected_tiles, open_passages},
+    };
+    use rand::{rngs::StdRng, SeedableRng};
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(Hex::ZERO)]
+    #[case(Hex::new(1, -1))]
+    #[case(Hex::new(-2, 2))]
+    fn wilson_spanning_tree(#[case] start: Hex) {
+        let mut maze = create_hex_maze(4);
+        let mut rng = StdRng::seed_from_u64(12345);
+
+        wilson(&mut maze, start, &mut rng, &mut ());
+
+        // A connected graph with one edge less than it has vertices is a tree
+        assert_eq!(
+            connected_tiles(&maze, start).len(),
+            maze.count(),
+            "All tiles should be reachable"
+        );
+        assert_eq!(
+            open_passages(&maze),
+            maze.count() - 1,
+            "Maze should be a spanning tree"
+        );
+    }
+
+    #[test]
+    fn wilson_deterministic() {
+        let mut maze1 = create_hex_maze(5);
+        let mut maze2 = create_hex_maze(5);
+
+        wilson(
+            &mut maze1,
+            Hex::ZERO,
+            &mut StdRng::seed_from_u64(12345),
+            &mut (),
+        );
+        wilson(
+            &mut maze2,
+            Hex::ZERO,
+            &mut StdRng::seed_from_u64(12345),
+            &mut (),
+        );
+
+        assert_eq!(maze1, maze2, "Same seed should produce identical mazes");
+    }
+}
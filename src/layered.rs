@@ -0,0 +1,365 @@
+use crate::{errors::MazeError, Maze, Tile, Walls};
+#[cfg(feature = "bevy")]
+use bevy::prelude::*;
+#[cfg(feature = "bevy_reflect")]
+use bevy_utils::HashSet;
+use hexx::{EdgeDirection, Hex};
+#[cfg(not(feature = "bevy_reflect"))]
+use std::collections::HashSet;
+
+/// A stack of hexagonal mazes, with stairs connecting tiles on consecutive levels.
+///
+/// Tiles are addressed by their position and level, `(Hex, usize)`, where level `0` is the
+/// bottom of the stack. Stairs lead from a tile up to the tile at the same position on the
+/// level above, and are open in both directions.
+///
+/// # Examples
+///
+/// ```
+/// use hexlab::prelude::*;
+///
+/// let mut maze = LayeredMaze::new();
+/// maze.push_level(Maze::from([Hex::ZERO, Hex::new(1, 0)]));
+/// maze.push_level(Maze::from([Hex::ZERO]));
+///
+/// assert_eq!(maze.add_stairs(&(Hex::ZERO, 0)), Ok(false));
+/// assert!(maze.has_stairs_up(&(Hex::ZERO, 0)));
+/// assert!(maze.has_stairs_down(&(Hex::ZERO, 1)));
+///
+/// // There is no tile above to lead to
+/// assert_eq!(
+///     maze.add_stairs(&(Hex::new(1, 0), 0)),
+///     Err(MazeError::InvalidCoordinate(Hex::new(1, 0)))
+/// );
+/// ```
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "bevy_reflect", derive(bevy_reflect::Reflect))]
+#[cfg_attr(feature = "bevy", derive(Component))]
+#[cfg_attr(feature = "bevy", reflect(Component))]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LayeredMaze {
+    levels: Vec<Maze>,
+    stairs: HashSet<(Hex, usize)>,
+}
+
+impl LayeredMaze {
+    /// Creates a new layered maze without any levels.
+    #[inline]
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a level on top of the stack, returning its index.
+    pub fn push_level(&mut self, maze: Maze) -> usize {
+        self.levels.push(maze);
+        self.levels.len() - 1
+    }
+
+    /// Returns the levels of the maze, from the bottom up.
+    #[inline]
+    #[must_use]
+    pub fn levels(&self) -> &[Maze] {
+        &self.levels
+    }
+
+    /// Returns the number of levels in the maze.
+    #[inline]
+    #[must_use]
+    pub const fn level_count(&self) -> usize {
+        self.levels.len()
+    }
+
+    /// Returns a reference to the maze of the given level.
+    #[inline]
+    #[must_use]
+    pub fn level(&self, level: usize) -> Option<&Maze> {
+        self.levels.get(level)
+    }
+
+    /// Returns a mutable reference to the maze of the given level.
+    #[inline]
+    #[must_use]
+    pub fn level_mut(&mut self, level: usize) -> Option<&mut Maze> {
+        self.levels.get_mut(level)
+    }
+
+    /// Returns the number of tiles on all levels.
+    #[must_use]
+    pub fn count(&self) -> usize {
+        self.levels.iter().map(Maze::count).sum()
+    }
+
+    /// Returns `true` if no level contains any tiles.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.levels.iter().all(Maze::is_empty)
+    }
+
+    /// Returns a reference to the tile at the specified position and level.
+    #[inline]
+    #[must_use]
+    pub fn get(&self, &(pos, level): &(Hex, usize)) -> Option<&Tile> {
+        self.level(level)?.get(&pos)
+    }
+
+    /// Returns a reference to the walls of the tile at the specified position and level.
+    #[inline]
+    #[must_use]
+    pub fn get_walls(&self, &(pos, level): &(Hex, usize)) -> Option<&Walls> {
+        self.level(level)?.get_walls(&pos)
+    }
+
+    /// Adds a wall to the tile at the specified position and level.
+    ///
+    /// Returns `true` if the wall was already present.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`MazeError::InvalidLevel`] if the level does not exist.
+    /// Returns [`MazeError::InvalidCoordinate`] if there is no tile at the position on the level.
+    pub fn add_tile_wall(
+        &mut self,
+        &(pos, level): &(Hex, usize),
+        direction: EdgeDirection,
+    ) -> Result<bool, MazeError> {
+        self.level_mut(level)
+            .ok_or(MazeError::InvalidLevel(level))?
+            .add_tile_wall(&pos, direction)
+    }
+
+    /// Removes a wall from the tile at the specified position and level.
+    ///
+    /// Returns `true` if the wall was present.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`MazeError::InvalidLevel`] if the level does not exist.
+    /// Returns [`MazeError::InvalidCoordinate`] if there is no tile at the position on the level.
+    pub fn remove_tile_wall(
+        &mut self,
+        &(pos, level): &(Hex, usize),
+        direction: EdgeDirection,
+    ) -> Result<bool, MazeError> {
+        self.level_mut(level)
+            .ok_or(MazeError::InvalidLevel(level))?
+            .remove_tile_wall(&pos, direction)
+    }
+
+    /// Adds stairs leading from the tile at the specified position and level up to the tile
+    /// at the same position on the level above.
+    ///
+    /// Returns `true` if the stairs were already present.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`MazeError::InvalidLevel`] if there is no level above.
+    /// Returns [`MazeError::InvalidCoordinate`] if there is no tile at the position on either
+    /// level.
+    pub fn add_stairs(&mut self, &(pos, level): &(Hex, usize)) -> Result<bool, MazeError> {
+        let above = level + 1;
+        if above >= self.levels.len() {
+            return Err(MazeError::InvalidLevel(above));
+        }
+        if self.get(&(pos, level)).is_none() || self.get(&(pos, above)).is_none() {
+            return Err(MazeError::InvalidCoordinate(pos));
+        }
+        Ok(!self.stairs.insert((pos, level)))
+    }
+
+    /// Removes the stairs leading up from the tile at the specified position and level.
+    ///
+    /// Returns `true` if the stairs were present.
+    pub fn remove_stairs(&mut self, coord: &(Hex, usize)) -> bool {
+        self.stairs.remove(coord)
+    }
+
+    /// Returns `true` if stairs lead up from the tile at the specified position and level.
+    #[inline]
+    #[must_use]
+    pub fn has_stairs_up(&self, coord: &(Hex, usize)) -> bool {
+        self.stairs.contains(coord)
+    }
+
+    /// Returns `true` if stairs lead down from the tile at the specified position and level.
+    #[must_use]
+    pub fn has_stairs_down(&self, &(pos, level): &(Hex, usize)) -> bool {
+        level
+            .checked_sub(1)
+            .is_some_and(|below| self.stairs.contains(&(pos, below)))
+    }
+
+    /// Returns the tiles that stairs lead up from, sorted by level and position.
+    #[must_use]
+    pub fn stairs(&self) -> Vec<(Hex, usize)> {
+        let mut stairs = self.stairs.iter().copied().collect::<Vec<_>>();
+        stairs.sort_unstable_by_key(|&(hex, level)| (level, hex.x, hex.y));
+        stairs
+    }
+
+    /// Returns the tiles reachable in a single move from the tile at the specified position
+    /// and level, through a missing wall or along stairs.
+    ///
+    /// A passage tunneling under a neighboring tile leads to the tile where it comes out, see
+    /// [`Tile::under_passage`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use hexlab::prelude::*;
+    ///
+    /// let mut maze = LayeredMaze::new();
+    /// maze.push_level(Maze::from([Hex::ZERO, Hex::new(1, 0)]));
+    /// maze.push_level(Maze::from([Hex::ZERO]));
+    /// maze.add_stairs(&(Hex::ZERO, 0)).unwrap();
+    ///
+    /// // Every tile starts out with all of its walls
+    /// assert_eq!(maze.passages(&(Hex::ZERO, 0)), vec![(Hex::ZERO, 1)]);
+    /// assert_eq!(maze.passages(&(Hex::ZERO, 1)), vec![(Hex::ZERO, 0)]);
+    /// ```
+    #[must_use]
+    pub fn passages(&self, coord: &(Hex, usize)) -> Vec<(Hex, usize)> {
+        self.moves(coord, None)
+            .into_iter()
+            .filter_map(|(mut next, mut under)| {
+                while under.is_some() {
+                    (next, under) = self.moves(&next, under).into_iter().next()?;
+                }
+                Some(next)
+            })
+            .collect()
+    }
+
+    /// Returns the tiles reachable in a single move, taking weave crossings and stairs into
+    /// account.
+    ///
+    /// Works like [`Maze::moves`] on the level of the tile, additionally moving along stairs
+    /// when on top of the tile.
+    pub(crate) fn moves(
+        &self,
+        coord: &(Hex, usize),
+        under: Option<EdgeDirection>,
+    ) -> Vec<((Hex, usize), Option<EdgeDirection>)> {
+        let (pos, level) = *coord;
+        let Some(maze) = self.level(level) else {
+            return Vec::new();
+        };
+        if maze.get(&pos).is_none() {
+            return Vec::new();
+        }
+
+        let mut moves = maze
+            .moves(pos, under)
+            .into_iter()
+            .map(|(neighbor, under)| ((neighbor, level), under))
+            .collect::<Vec<_>>();
+        if under.is_none() && self.has_stairs_up(coord) {
+            moves.push(((pos, level + 1), None));
+        }
+        if under.is_none() && self.has_stairs_down(coord) {
+            moves.push(((pos, level - 1), None));
+        }
+        moves
+    }
+}
+
+impl FromIterator<Maze> for LayeredMaze {
+    fn from_iter<T: IntoIterator<Item = Maze>>(iter: T) -> Self {
+        Self {
+            levels: iter.into_iter().collect(),
+            stairs: HashSet::default(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::builder::create_hex_maze;
+
+    fn two_levels() -> LayeredMaze {
+        [create_hex_maze(1), create_hex_maze(1)]
+            .into_iter()
+            .collect()
+    }
+
+    #[test]
+    fn stairs_connect_both_levels() {
+        let mut maze = two_levels();
+        let pos = Hex::new(1, 0);
+
+        assert_eq!(maze.add_stairs(&(pos, 0)), Ok(false));
+        assert_eq!(maze.add_stairs(&(pos, 0)), Ok(true));
+        assert!(maze.has_stairs_up(&(pos, 0)));
+        assert!(maze.has_stairs_down(&(pos, 1)));
+        assert!(!maze.has_stairs_down(&(pos, 0)));
+        assert!(!maze.has_stairs_up(&(pos, 1)));
+        assert_eq!(maze.stairs(), vec![(pos, 0)]);
+
+        assert!(maze.remove_stairs(&(pos, 0)));
+        assert!(!maze.remove_stairs(&(pos, 0)));
+        assert!(!maze.has_stairs_down(&(pos, 1)));
+    }
+
+    #[test]
+    fn stairs_need_level_above() {
+        let mut maze = two_levels();
+        assert_eq!(
+            maze.add_stairs(&(Hex::ZERO, 1)),
+            Err(MazeError::InvalidLevel(2))
+        );
+        assert_eq!(
+            maze.add_stairs(&(Hex::new(5, 0), 0)),
+            Err(MazeError::InvalidCoordinate(Hex::new(5, 0)))
+        );
+    }
+
+    #[test]
+    fn passages_follow_walls_and_stairs() {
+        let mut maze = two_levels();
+        let east = EdgeDirection::FLAT_SOUTH_EAST;
+        let neighbor = Hex::ZERO + east;
+
+        assert!(maze.passages(&(Hex::ZERO, 0)).is_empty());
+        assert!(maze.passages(&(Hex::ZERO, 5)).is_empty());
+
+        assert_eq!(maze.remove_tile_wall(&(Hex::ZERO, 0), east), Ok(true));
+        assert_eq!(
+            maze.remove_tile_wall(&(Hex::ZERO, 3), east),
+            Err(MazeError::InvalidLevel(3))
+        );
+        assert_eq!(maze.add_stairs(&(Hex::ZERO, 0)), Ok(false));
+
+        assert_eq!(
+            maze.passages(&(Hex::ZERO, 0)),
+            vec![(neighbor, 0), (Hex::ZERO, 1)]
+        );
+        assert_eq!(maze.passages(&(Hex::ZERO, 1)), vec![(Hex::ZERO, 0)]);
+    }
+
+    #[test]
+    fn passages_follow_tunnels() {
+        let (west, east) = (Hex::new(-1, 0), Hex::new(1, 0));
+        let direction = EdgeDirection::FLAT_SOUTH_EAST;
+        assert_eq!(west + direction, Hex::ZERO);
+        let mut maze = two_levels();
+        let _ = maze.remove_tile_wall(&(west, 0), direction);
+        let _ = maze.remove_tile_wall(&(east, 0), direction.const_neg());
+        if let Some(tile) = maze.levels[0].get_mut(&Hex::ZERO) {
+            tile.set_under_passage(Some(direction));
+        }
+
+        assert_eq!(maze.passages(&(west, 0)), vec![(east, 0)]);
+        assert_eq!(maze.passages(&(east, 0)), vec![(west, 0)]);
+        assert!(maze.passages(&(Hex::ZERO, 0)).is_empty());
+    }
+
+    #[test]
+    fn count_spans_levels() {
+        let maze = two_levels();
+        assert_eq!(maze.level_count(), 2);
+        assert_eq!(maze.count(), 14);
+        assert!(!maze.is_empty());
+        assert!(LayeredMaze::new().is_empty());
+    }
+}
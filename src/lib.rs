@@ -3,8 +3,17 @@
 //! # Features
 //!
 //! - Create hexagonal mazes of configurable size
+//! - Rectangular, triangular, ring and spiral board shapes
+//! - Wrap-around boards where each edge connects to the opposite one
+//! - Multi-level mazes with stairs between the levels
+//! - Weave mazes with passages crossing under each other
+//! - Directional bias towards straight or axis-aligned corridors
 //! - Customizable maze properties (radius, start position, seed)
 //! - Efficient bit-flag representation of walls
+//! - Validation and repair of mazes loaded from disk or edited by hand
+//! - Dense array-backed storage for boards with millions of tiles
+//! - Edge-based storage keeping each wall once, on the edge shared by two tiles
+//! - Custom per-tile data attached to mazes and tiles
 //! - Multiple maze generation algorithms
 //! - Maze builder pattern for easy maze creation
 //!
@@ -48,26 +57,47 @@
 //! assert!(walls.contains(EdgeDirection::FLAT_NORTH));
 //! assert!(!walls.contains(EdgeDirection::FLAT_SOUTH));
 //!```
+// `bevy_reflect::Reflect` derive binds struct variant fields with underscore-prefixed names
+#![cfg_attr(feature = "bevy_reflect", allow(clippy::used_underscore_binding))]
+
 mod builder;
+mod dense;
+mod edges;
 pub mod errors;
 mod generator;
+mod layered;
 mod maze;
 #[cfg(feature = "pathfinding")]
 mod pathfinding;
+mod shape;
 mod tile;
+mod topology;
 pub mod traits;
+mod validate;
 mod walls;
 
 pub use builder::MazeBuilder;
+pub use dense::DenseMaze;
+pub use edges::EdgeMaze;
 pub use errors::*;
-pub use generator::GeneratorType;
+pub use generator::{
+    DirectionBias, GenerationStep, GenerationSteps, GeneratorType, GrowingTreePolicy,
+};
+pub use layered::LayeredMaze;
 pub use maze::Maze;
+pub use shape::MazeShape;
 pub use tile::Tile;
+pub use topology::Topology;
 pub use traits::*;
+pub use validate::{RepairPolicy, ValidationReport};
 pub use walls::Walls;
 
 /// Prelude module containing commonly used types
 pub mod prelude {
-    pub use super::{errors::*, traits::*, GeneratorType, Maze, MazeBuilder, Tile, Walls};
+    pub use super::{
+        errors::*, traits::*, DenseMaze, DirectionBias, EdgeMaze, GenerationStep, GenerationSteps,
+        GeneratorType, GrowingTreePolicy, LayeredMaze, Maze, MazeBuilder, MazeShape, RepairPolicy,
+        Tile, Topology, ValidationReport, Walls,
+    };
     pub use hexx::{EdgeDirection, Hex, HexLayout};
 }
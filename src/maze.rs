@@ -1,7 +1,8 @@
 use super::{Tile, Walls};
 use crate::{
     errors::MazeError,
-    traits::{TilePosition, WallStorage},
+    traits::{MazeStorage, TilePosition, WallStorage},
+    Topology,
 };
 #[cfg(feature = "bevy")]
 use bevy::prelude::*;
@@ -15,13 +16,18 @@ use std::ops::{Deref, DerefMut};
 /// Represents a hexagonal maze with tiles and walls.
 ///
 /// This struct stores the layout of a hexagonal maze, including the positions
-/// of tiles and their associated walls.
-#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// of tiles and their associated walls, and the [`Topology`] connecting its edges.
+///
+/// Mazes serialize as their tiles followed by their topology. Mazes serialized by earlier
+/// versions, without a topology, still load with [`Topology::Bounded`].
 #[cfg_attr(feature = "bevy_reflect", derive(bevy_reflect::Reflect))]
 #[cfg_attr(feature = "bevy", derive(Component))]
 #[cfg_attr(feature = "bevy", reflect(Component))]
 #[derive(Debug, Clone, Default, PartialEq, Eq)]
-pub struct Maze(HashMap<Hex, Tile>);
+pub struct Maze<T = ()> {
+    tiles: HashMap<Hex, Tile<T>>,
+    topology: Topology,
+}
 
 impl Maze {
     /// Creates a new empty maze
@@ -42,35 +48,13 @@ impl Maze {
         Self::default()
     }
 
-    /// Inserts a new tile at the specified coordinates.
-    ///
-    /// If the map did not have this key present, [`None`] is returned.
-    ///
-    /// If the map did have this key present, the value is updated, and the old
-    /// value is returned.
-    ///
-    /// # Arguments
-    ///
-    /// - `coords` - The hexagonal coordinates where the tile should be added.
-    ///
-    /// # Examples
-    ///
-    /// ```
-    /// use hexlab::prelude::*;
-    ///
-    /// let mut maze = Maze::new();
-    /// let coord = Hex::ZERO;
-    ///
-    /// assert_eq!(maze.insert(coord), None);
-    /// assert_eq!(maze.insert(coord), Some(Tile::new(coord)));
-    /// ```
-    pub fn insert(&mut self, coords: Hex) -> Option<Tile> {
-        let tile = Tile::new(coords);
-        self.0.insert(coords, tile)
-    }
-
     /// Adds a new tile at the specified coordinates. It is recommended to use [`insert`].
     ///
+    /// Only available for mazes without tile data: [`MazeError::TileAlreadyExists`] carries the
+    /// replaced tile, and making the error generic over the tile data would make every
+    /// [`MazeError`] generic with it. For other tile data, insert through [`Maze::insert`] and
+    /// [`Maze::data_mut`], or into the underlying map, which returns the replaced tile.
+    ///
     /// [`insert`]: Maze::insert
     ///
     /// # Arguments
@@ -114,7 +98,7 @@ impl Maze {
                 insert_pos: coords,
             });
         }
-        self.0
+        self.tiles
             .insert(coords, tile.clone())
             .map_or(Ok(tile), |old_tile| {
                 Err(MazeError::TileAlreadyExists {
@@ -123,7 +107,38 @@ impl Maze {
                 })
             })
     }
+}
 
+impl<T: Default> Maze<T> {
+    /// Inserts a new tile at the specified coordinates.
+    ///
+    /// If the map did not have this key present, [`None`] is returned.
+    ///
+    /// If the map did have this key present, the value is updated, and the old
+    /// value is returned.
+    ///
+    /// # Arguments
+    ///
+    /// - `coords` - The hexagonal coordinates where the tile should be added.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use hexlab::prelude::*;
+    ///
+    /// let mut maze = Maze::new();
+    /// let coord = Hex::ZERO;
+    ///
+    /// assert_eq!(maze.insert(coord), None);
+    /// assert_eq!(maze.insert(coord), Some(Tile::new(coord)));
+    /// ```
+    pub fn insert(&mut self, coords: Hex) -> Option<Tile<T>> {
+        let tile = Tile::with_data(coords, T::default());
+        self.tiles.insert(coords, tile)
+    }
+}
+
+impl<T> Maze<T> {
     /// Returns a reference to the tile at the specified coordinates.
     ///
     /// # Arguments
@@ -144,14 +159,84 @@ impl Maze {
     /// ```
     #[inline]
     #[must_use]
-    pub fn get(&self, coord: &Hex) -> Option<&Tile> {
-        self.0.get(coord)
+    pub fn get(&self, coord: &Hex) -> Option<&Tile<T>> {
+        self.tiles.get(coord)
     }
 
     #[inline]
     #[must_use]
-    pub fn get_mut(&mut self, coord: &Hex) -> Option<&mut Tile> {
-        self.0.get_mut(coord)
+    pub fn get_mut(&mut self, coord: &Hex) -> Option<&mut Tile<T>> {
+        self.tiles.get_mut(coord)
+    }
+
+    /// Returns a reference to the data of the tile at the specified coordinates.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use hexlab::prelude::*;
+    ///
+    /// let maze = Maze::from([Tile::with_data(Hex::ZERO, 3)]);
+    ///
+    /// assert_eq!(maze.data(&Hex::ZERO), Some(&3));
+    /// assert_eq!(maze.data(&Hex::new(1, 1)), None);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn data(&self, coord: &Hex) -> Option<&T> {
+        self.tiles.get(coord).map(Tile::data)
+    }
+
+    /// Returns a mutable reference to the data of the tile at the specified coordinates.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use hexlab::prelude::*;
+    ///
+    /// let mut maze = Maze::<u32>::default();
+    /// maze.insert(Hex::ZERO);
+    ///
+    /// if let Some(visits) = maze.data_mut(&Hex::ZERO) {
+    ///     *visits += 1;
+    /// }
+    /// assert_eq!(maze.data(&Hex::ZERO), Some(&1));
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn data_mut(&mut self, coord: &Hex) -> Option<&mut T> {
+        self.tiles.get_mut(coord).map(Tile::data_mut)
+    }
+
+    /// Converts the data of every tile with `f`, keeping the walls and the [`Topology`].
+    ///
+    /// Generators build mazes without tile data, this attaches data to one afterwards.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use hexlab::prelude::*;
+    ///
+    /// let maze = MazeBuilder::new()
+    ///     .with_radius(3)
+    ///     .with_seed(12345)
+    ///     .build()
+    ///     .expect("Failed to create maze");
+    ///
+    /// let distances = maze.map_data(|pos, ()| pos.unsigned_distance_to(Hex::ZERO));
+    /// assert_eq!(distances.data(&Hex::ZERO), Some(&0));
+    /// assert_eq!(distances.data(&Hex::new(2, 1)), Some(&3));
+    /// ```
+    #[must_use]
+    pub fn map_data<U>(self, mut f: impl FnMut(Hex, T) -> U) -> Maze<U> {
+        Maze {
+            tiles: self
+                .tiles
+                .into_iter()
+                .map(|(pos, tile)| (pos, tile.map_data(|data| f(pos, data))))
+                .collect(),
+            topology: self.topology,
+        }
     }
 
     /// Returns an optional mutable reference to the walls at the specified coordinates.
@@ -176,7 +261,7 @@ impl Maze {
     #[inline]
     #[must_use]
     pub fn get_walls(&self, coord: &Hex) -> Option<&Walls> {
-        self.0.get(coord).map(Tile::walls)
+        self.tiles.get(coord).map(Tile::walls)
     }
 
     /// Returns an optional mutable reference to the walls at the specified coordinates.
@@ -201,7 +286,7 @@ impl Maze {
     #[inline]
     #[must_use]
     pub fn get_walls_mut(&mut self, coord: &Hex) -> Option<&mut Walls> {
-        self.0.get_mut(coord).map(Tile::walls_mut)
+        self.tiles.get_mut(coord).map(Tile::walls_mut)
     }
 
     /// Returns the number of tiles in the maze.
@@ -223,7 +308,7 @@ impl Maze {
     #[inline]
     #[must_use]
     pub fn count(&self) -> usize {
-        self.0.len()
+        self.tiles.len()
     }
 
     /// Returns `true` if the maze contains no tiles.
@@ -242,7 +327,7 @@ impl Maze {
     #[inline]
     #[must_use]
     pub fn is_empty(&self) -> bool {
-        self.0.is_empty()
+        self.tiles.is_empty()
     }
 
     /// Adds a wall from a tile in the specified direction.
@@ -292,7 +377,7 @@ impl Maze {
         coord: &Hex,
         direction: EdgeDirection,
     ) -> Result<bool, MazeError> {
-        self.0
+        self.tiles
             .get_mut(coord)
             .map(|tile| tile.walls.insert(direction))
             .ok_or(MazeError::InvalidCoordinate(*coord))
@@ -329,52 +414,323 @@ impl Maze {
         coord: &Hex,
         direction: EdgeDirection,
     ) -> Result<bool, MazeError> {
-        self.0
+        self.tiles
             .get_mut(coord)
             .map(|tile| tile.walls.remove(direction))
             .ok_or(MazeError::InvalidCoordinate(*coord))
     }
+
+    /// Removes the wall between a tile and its neighbor in the specified direction, on both
+    /// sides, wrapping around the edges of the maze according to its [`Topology`].
+    ///
+    /// Returns `true` if the wall was present on either side.
+    ///
+    /// # Arguments
+    ///
+    /// - `coord` - The hexagonal coordinates of the tile.
+    /// - `direction` - The direction of the neighbor to open a passage to.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`MazeError::InvalidCoordinate`] if the specified coordinate does not exist in the maze.
+    /// Returns [`MazeError::NoNeighbor`] if there is no tile in the specified direction.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use hexlab::prelude::*;
+    ///
+    /// let neighbor = Hex::ZERO + EdgeDirection::FLAT_NORTH;
+    /// let mut maze = Maze::from([Hex::ZERO, neighbor]);
+    ///
+    /// assert_eq!(maze.carve(&Hex::ZERO, EdgeDirection::FLAT_NORTH), Ok(true));
+    /// assert!(!maze.get_walls(&Hex::ZERO).unwrap().contains(EdgeDirection::FLAT_NORTH));
+    /// assert!(!maze.get_walls(&neighbor).unwrap().contains(EdgeDirection::FLAT_SOUTH));
+    ///
+    /// assert_eq!(
+    ///     maze.carve(&Hex::ZERO, EdgeDirection::FLAT_SOUTH),
+    ///     Err(MazeError::NoNeighbor {
+    ///         coord: Hex::ZERO,
+    ///         direction: EdgeDirection::FLAT_SOUTH
+    ///     })
+    /// );
+    /// ```
+    pub fn carve(&mut self, coord: &Hex, direction: EdgeDirection) -> Result<bool, MazeError> {
+        let neighbor = self.shared_edge(*coord, direction)?;
+        let present = self.remove_tile_wall(coord, direction)?;
+        Ok(self.remove_tile_wall(&neighbor, direction.const_neg())? || present)
+    }
+
+    /// Adds the wall between a tile and its neighbor in the specified direction, on both
+    /// sides, wrapping around the edges of the maze according to its [`Topology`].
+    ///
+    /// Returns `true` if the wall was already present on both sides.
+    ///
+    /// # Arguments
+    ///
+    /// - `coord` - The hexagonal coordinates of the tile.
+    /// - `direction` - The direction of the neighbor to wall off.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`MazeError::InvalidCoordinate`] if the specified coordinate does not exist in the maze.
+    /// Returns [`MazeError::NoNeighbor`] if there is no tile in the specified direction.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use hexlab::prelude::*;
+    ///
+    /// let neighbor = Hex::ZERO + EdgeDirection::FLAT_NORTH;
+    /// let mut maze = Maze::from([Hex::ZERO, neighbor]);
+    /// maze.open_shared_walls();
+    ///
+    /// assert_eq!(maze.wall_up(&Hex::ZERO, EdgeDirection::FLAT_NORTH), Ok(false));
+    /// assert_eq!(maze.wall_up(&neighbor, EdgeDirection::FLAT_SOUTH), Ok(true));
+    /// assert!(maze.get_walls(&Hex::ZERO).unwrap().contains(EdgeDirection::FLAT_NORTH));
+    /// ```
+    pub fn wall_up(&mut self, coord: &Hex, direction: EdgeDirection) -> Result<bool, MazeError> {
+        let neighbor = self.shared_edge(*coord, direction)?;
+        let present = self.add_tile_wall(coord, direction)?;
+        Ok(self.add_tile_wall(&neighbor, direction.const_neg())? && present)
+    }
+
+    /// Returns the neighbor sharing the edge of the tile at `coord` in `direction`.
+    fn shared_edge(&self, coord: Hex, direction: EdgeDirection) -> Result<Hex, MazeError> {
+        if !self.tiles.contains_key(&coord) {
+            return Err(MazeError::InvalidCoordinate(coord));
+        }
+        self.neighbor(coord, direction)
+            .ok_or(MazeError::NoNeighbor { coord, direction })
+    }
+
+    /// Returns the topology connecting the edges of the maze.
+    #[inline]
+    #[must_use]
+    pub const fn topology(&self) -> Topology {
+        self.topology
+    }
+
+    /// Sets the topology connecting the edges of the maze.
+    ///
+    /// Walls are left untouched, so walls across a seam that is joined or split by the new
+    /// topology may need to be updated.
+    #[inline]
+    pub const fn set_topology(&mut self, topology: Topology) {
+        self.topology = topology;
+    }
+
+    /// Returns the position of the tile next to `pos` in `direction`, wrapping around the
+    /// edges of the maze according to its [`Topology`].
+    ///
+    /// Returns [`None`] if there is no tile in that direction. A tile is never its own
+    /// neighbor, even on a board small enough to wrap onto itself.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use hexlab::prelude::*;
+    ///
+    /// let mut maze = MazeBuilder::new()
+    ///     .with_shape(MazeShape::Parallelogram)
+    ///     .with_dimensions(4, 4)
+    ///     .build()
+    ///     .expect("Failed to create maze");
+    /// let corner = Hex::new(2, 0);
+    /// assert_eq!(maze.neighbor(corner, EdgeDirection::FLAT_SOUTH), Some(Hex::new(2, 1)));
+    /// assert_eq!(maze.neighbor(corner, EdgeDirection::FLAT_NORTH_EAST), None);
+    ///
+    /// maze.set_topology(Topology::Parallelogram { width: 4, height: 4 });
+    /// assert_eq!(
+    ///     maze.neighbor(corner, EdgeDirection::FLAT_NORTH_EAST),
+    ///     Some(Hex::new(-1, -1))
+    /// );
+    /// ```
+    #[must_use]
+    pub fn neighbor(&self, pos: Hex, direction: EdgeDirection) -> Option<Hex> {
+        let neighbor = self.topology.wrap(pos + direction);
+        (neighbor != pos && self.tiles.contains_key(&neighbor)).then_some(neighbor)
+    }
+
+    /// Returns the positions reachable in a single move, taking weave crossings into account.
+    ///
+    /// A position is paired with the direction it is being passed under in, or [`None`] if it
+    /// is entered on top. Moving on top leaves through a missing wall of the tile, while moving
+    /// under a tile continues straight on into the neighbor beyond it.
+    pub(crate) fn moves(
+        &self,
+        pos: Hex,
+        under: Option<EdgeDirection>,
+    ) -> Vec<(Hex, Option<EdgeDirection>)> {
+        let Some(tile) = self.tiles.get(&pos) else {
+            return Vec::new();
+        };
+        let directions = under.map_or_else(
+            || {
+                EdgeDirection::ALL_DIRECTIONS
+                    .into_iter()
+                    .filter(|&direction| !tile.walls.contains(direction))
+                    .collect()
+            },
+            |direction| vec![direction],
+        );
+
+        directions
+            .into_iter()
+            .filter_map(|direction| {
+                let neighbor = self.neighbor(pos, direction)?;
+                let next = self.tiles.get(&neighbor)?;
+                // Coming out from under a tile, the wall on this side must be open instead
+                if under.is_some() && next.walls.contains(direction.const_neg()) {
+                    return None;
+                }
+                let under = next.has_under_passage(direction).then_some(direction);
+                Some((neighbor, under))
+            })
+            .collect()
+    }
+
+    /// Removes every wall shared between two tiles of the maze.
+    ///
+    /// Walls facing positions without a tile are kept, so the maze stays enclosed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use hexlab::prelude::*;
+    ///
+    /// let mut maze = Maze::from([Hex::ZERO, Hex::ZERO + EdgeDirection::FLAT_NORTH]);
+    /// maze.open_shared_walls();
+    ///
+    /// let walls = maze.get_walls(&Hex::ZERO).unwrap();
+    /// assert!(!walls.contains(EdgeDirection::FLAT_NORTH));
+    /// assert!(walls.contains(EdgeDirection::FLAT_SOUTH));
+    /// assert_eq!(walls.count(), 5);
+    /// ```
+    pub fn open_shared_walls(&mut self) {
+        let shared = self
+            .tiles
+            .keys()
+            .map(|&pos| {
+                let walls = EdgeDirection::ALL_DIRECTIONS
+                    .into_iter()
+                    .filter(|&direction| self.neighbor(pos, direction).is_some())
+                    .collect::<Walls>();
+                (pos, walls)
+            })
+            .collect::<Vec<_>>();
+
+        for (pos, walls) in shared {
+            if let Some(tile) = self.tiles.get_mut(&pos) {
+                tile.walls.remove(walls);
+            }
+        }
+    }
+}
+
+impl<T> MazeStorage for Maze<T> {
+    fn count(&self) -> usize {
+        Self::count(self)
+    }
+
+    fn get_walls(&self, coord: &Hex) -> Option<&Walls> {
+        Self::get_walls(self, coord)
+    }
+
+    fn neighbor(&self, pos: Hex, direction: EdgeDirection) -> Option<Hex> {
+        Self::neighbor(self, pos, direction)
+    }
+
+    fn carve(&mut self, coord: &Hex, direction: EdgeDirection) -> Result<bool, MazeError> {
+        Self::carve(self, coord, direction)
+    }
+
+    fn wall_up(&mut self, coord: &Hex, direction: EdgeDirection) -> Result<bool, MazeError> {
+        Self::wall_up(self, coord, direction)
+    }
 }
 
-impl FromIterator<Hex> for Maze {
-    fn from_iter<T: IntoIterator<Item = Hex>>(iter: T) -> Self {
-        Self(iter.into_iter().map(|hex| (hex, Tile::new(hex))).collect())
+impl<T: Default> FromIterator<Hex> for Maze<T> {
+    /// Collects positions into tiles with all walls present and default data.
+    fn from_iter<I: IntoIterator<Item = Hex>>(iter: I) -> Self {
+        iter.into_iter()
+            .map(|hex| (hex, Tile::with_data(hex, T::default())))
+            .collect()
     }
 }
 
-impl FromIterator<Tile> for Maze {
-    fn from_iter<T: IntoIterator<Item = Tile>>(iter: T) -> Self {
-        Self(iter.into_iter().map(|tile| (tile.pos(), tile)).collect())
+impl<T> FromIterator<Tile<T>> for Maze<T> {
+    fn from_iter<I: IntoIterator<Item = Tile<T>>>(iter: I) -> Self {
+        iter.into_iter().map(|tile| (tile.pos(), tile)).collect()
     }
 }
 
-impl FromIterator<(Hex, Tile)> for Maze {
-    fn from_iter<T: IntoIterator<Item = (Hex, Tile)>>(iter: T) -> Self {
-        Self(iter.into_iter().collect())
+impl<T> FromIterator<(Hex, Tile<T>)> for Maze<T> {
+    fn from_iter<I: IntoIterator<Item = (Hex, Tile<T>)>>(iter: I) -> Self {
+        Self {
+            tiles: iter.into_iter().collect(),
+            topology: Topology::default(),
+        }
     }
 }
 
 impl<const N: usize> From<[Hex; N]> for Maze {
+    /// Creates a maze without tile data from an array of positions.
+    ///
+    /// Unlike collecting positions, this is not generic over the tile data, so `Maze::from`
+    /// needs no type annotations. Collect into a `Maze<T>` for other tile data.
     fn from(value: [Hex; N]) -> Self {
         value.into_iter().collect()
     }
 }
 
-impl<const N: usize> From<[Tile; N]> for Maze {
-    fn from(value: [Tile; N]) -> Self {
+impl<T, const N: usize> From<[Tile<T>; N]> for Maze<T> {
+    fn from(value: [Tile<T>; N]) -> Self {
         value.into_iter().collect()
     }
 }
 
-impl Deref for Maze {
-    type Target = HashMap<Hex, Tile>;
+#[cfg(feature = "serde")]
+impl<T: serde::Serialize> serde::Serialize for Maze<T> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serde::Serialize::serialize(&SerializedMaze(&self.tiles, &self.topology), serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T: serde::Deserialize<'de> + Default> serde::Deserialize<'de> for Maze<T> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let DeserializedMaze(tiles, topology) = serde::Deserialize::deserialize(deserializer)?;
+        Ok(Self { tiles, topology })
+    }
+}
+
+/// The serialized layout of a maze: its tiles followed by its topology.
+///
+/// The tiles come first so mazes serialized before topologies existed still load, with a
+/// bounded topology.
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize)]
+#[serde(rename = "Maze")]
+struct SerializedMaze<'a, T>(&'a HashMap<Hex, Tile<T>>, &'a Topology);
+
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+#[serde(
+    rename = "Maze",
+    bound(deserialize = "T: serde::Deserialize<'de> + Default")
+)]
+struct DeserializedMaze<T>(HashMap<Hex, Tile<T>>, #[serde(default)] Topology);
+
+impl<T> Deref for Maze<T> {
+    type Target = HashMap<Hex, Tile<T>>;
     fn deref(&self) -> &Self::Target {
-        &self.0
+        &self.tiles
     }
 }
 
-impl DerefMut for Maze {
+impl<T> DerefMut for Maze<T> {
     fn deref_mut(&mut self) -> &mut Self::Target {
-        &mut self.0
+        &mut self.tiles
     }
 }
@@ -21,19 +21,27 @@
 //!
 //! The pathfinding algorithm uses Manhattan distance as a heuristic and considers
 //! walls between cells when determining valid paths. Each step between adjacent
-//! cells has a cost of 1.
+//! cells has a cost of 1. On wrapping mazes the heuristic is not admissible, so it is
+//! left out.
+//!
+//! Layered mazes are searched the same way, with stairs between levels costing 1 as well.
 use hexx::{EdgeDirection, Hex};
 use pathfinding::prelude::*;
 
-use crate::Maze;
+use crate::{LayeredMaze, Maze};
 
-impl Maze {
+impl<T> Maze<T> {
     #[must_use]
     /// Finds the shortest path between two hexagonal positions in the maze using A* pathfinding.
     ///
     /// This function calculates the optimal path while taking into account walls between cells.
     /// The path cost between adjacent cells is always 1, and Manhattan distance is used as the
-    /// heuristic for pathfinding.
+    /// heuristic for pathfinding. Paths may cross the seams of a wrapping [`Topology`], and pass
+    /// under tiles through their under-passages, see [`Tile::under_passage`]. A tile passed both
+    /// on top and underneath appears twice in the path.
+    ///
+    /// [`Topology`]: crate::Topology
+    /// [`Tile::under_passage`]: crate::Tile::under_passage
     ///
     /// # Arguments
     ///
@@ -58,27 +66,81 @@ impl Maze {
     /// assert!(maze.find_path(Hex::ZERO, Hex::new(-1, 3)).is_some());
     /// ```
     pub fn find_path(&self, from: Hex, to: Hex) -> Option<Vec<Hex>> {
-        let successors = |pos: &Hex| {
-            {
-                EdgeDirection::ALL_DIRECTIONS.iter().filter_map(|&dir| {
-                    let neighbor = pos.neighbor(dir);
-                    if let Some(current_tile) = self.get(pos) {
-                        if self.get(&neighbor).is_some() && !current_tile.walls.contains(dir) {
-                            return Some((neighbor, 1)); // Cost of 1 for each step
-                        }
-                    }
-                    None
-                })
-            }
-            .collect::<Vec<_>>()
+        // Tiles are entered either on top or passing under them, see `Maze::moves`
+        let successors = |&(pos, under): &(Hex, Option<EdgeDirection>)| {
+            self.moves(pos, under).into_iter().map(|next| (next, 1)) // Cost of 1 for each step
         };
 
-        let heuristic = |pos: &Hex| {
+        let heuristic = |(pos, _): &(Hex, Option<EdgeDirection>)| {
+            // Paths across the seams of a wrapping maze can be shorter than the distance
+            if self.topology().is_wrapping() {
+                return 0;
+            }
             // Manhatan distance
             let diff = *pos - to;
             (diff.x.abs() + diff.y.abs() + diff.z().abs()) / 2
         };
 
-        astar(&from, successors, heuristic, |pos| *pos == to).map(|(path, _)| path)
+        astar(&(from, None), successors, heuristic, |&(pos, under)| {
+            pos == to && under.is_none()
+        })
+        .map(|(path, _)| path.into_iter().map(|(pos, _)| pos).collect())
+    }
+}
+
+impl LayeredMaze {
+    /// Finds the shortest path between two tiles of the layered maze using A* pathfinding.
+    ///
+    /// Works like [`Maze::find_path`], additionally moving between levels along stairs. Every
+    /// move, horizontal or vertical, costs 1. Paths pass under tiles through their
+    /// under-passages, so a tile passed underneath appears in the path like any other.
+    ///
+    /// # Arguments
+    ///
+    /// * `from` - The starting position and level
+    /// * `to` - The target position and level
+    ///
+    /// # Returns
+    ///
+    /// * `Some(Vec<(Hex, usize)>)` - The positions and levels of the path from start to target
+    /// * `None` - If no valid path exists between the positions
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use hexlab::prelude::*;
+    ///
+    /// let maze = MazeBuilder::new()
+    ///     .with_radius(3)
+    ///     .with_levels(3)
+    ///     .with_seed(12345)
+    ///     .build_layered()
+    ///     .expect("Failed to create maze");
+    /// assert!(maze.find_path((Hex::ZERO, 0), (Hex::new(-1, 3), 2)).is_some());
+    /// ```
+    #[must_use]
+    pub fn find_path(&self, from: (Hex, usize), to: (Hex, usize)) -> Option<Vec<(Hex, usize)>> {
+        let wrapping = self
+            .levels()
+            .iter()
+            .any(|maze| maze.topology().is_wrapping());
+        let heuristic = |&((pos, level), _): &((Hex, usize), Option<EdgeDirection>)| {
+            let levels = u32::try_from(level.abs_diff(to.1)).unwrap_or(u32::MAX);
+            // Paths across the seams of a wrapping maze can be shorter than the distance
+            if wrapping {
+                return levels;
+            }
+            pos.unsigned_distance_to(to.0).saturating_add(levels)
+        };
+
+        // Tiles are entered either on top or passing under them, see `Maze::moves`
+        let successors = |(coord, under): &((Hex, usize), Option<EdgeDirection>)| {
+            self.moves(coord, *under).into_iter().map(|next| (next, 1))
+        };
+
+        astar(&(from, None), successors, heuristic, |&(coord, under)| {
+            coord == to && under.is_none()
+        })
+        .map(|(path, _)| path.into_iter().map(|(coord, _)| coord).collect())
     }
 }
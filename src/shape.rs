@@ -0,0 +1,236 @@
+use crate::{errors::MazeBuilderError, Maze};
+use hexx::{shapes, Hex};
+
+/// The shape of the tile set a maze is generated on.
+///
+/// Radius-based shapes use the radius set with [`MazeBuilder::with_radius`], while
+/// rectangular shapes use the dimensions set with [`MazeBuilder::with_dimensions`]. All
+/// shapes are placed around [`Hex::ZERO`].
+///
+/// [`MazeBuilder::with_radius`]: crate::MazeBuilder::with_radius
+/// [`MazeBuilder::with_dimensions`]: crate::MazeBuilder::with_dimensions
+///
+/// # Examples
+///
+/// ```
+/// use hexlab::prelude::*;
+///
+/// let maze = MazeBuilder::new()
+///     .with_shape(MazeShape::FlatRectangle)
+///     .with_dimensions(8, 5)
+///     .build()
+///     .expect("Failed to create maze");
+/// assert_eq!(maze.count(), 40);
+///
+/// let maze = MazeBuilder::new()
+///     .with_shape(MazeShape::Ring { width: 2 })
+///     .with_radius(3)
+///     .build()
+///     .expect("Failed to create maze");
+/// assert_eq!(maze.count(), 30);
+/// ```
+#[allow(clippy::module_name_repetitions)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "bevy_reflect", derive(bevy_reflect::Reflect))]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum MazeShape {
+    /// A hexagon of the given radius.
+    #[default]
+    Hexagon,
+    /// A rectangle of flat topped hexagons, `width` columns by `height` rows.
+    FlatRectangle,
+    /// A rectangle of pointy topped hexagons, `width` columns by `height` rows.
+    PointyRectangle,
+    /// A parallelogram of `width` by `height` tiles, along the `x` and `y` axes.
+    Parallelogram,
+    /// A triangle with sides of `radius + 1` tiles, with a corner at [`Hex::ZERO`].
+    Triangle,
+    /// The outermost `width` rings of a hexagon of the given radius.
+    ///
+    /// The center is left out unless `width` exceeds the radius.
+    Ring { width: u16 },
+    /// A hexagon of the given radius, split by a single wall of missing tiles spiraling out
+    /// from the center.
+    ///
+    /// The arm of the spiral between two turns of the wall is `arm_width` tiles wide, and at
+    /// least one.
+    Spiral { arm_width: u16 },
+}
+
+impl MazeShape {
+    /// Returns `true` if the shape is sized with a radius, rather than dimensions.
+    #[inline]
+    #[must_use]
+    pub const fn uses_radius(self) -> bool {
+        !matches!(
+            self,
+            Self::FlatRectangle | Self::PointyRectangle | Self::Parallelogram
+        )
+    }
+
+    /// Creates the tiles of the shape with all walls present.
+    pub(crate) fn create_maze(
+        self,
+        radius: Option<u16>,
+        dimensions: Option<(u16, u16)>,
+    ) -> Result<Maze, MazeBuilderError> {
+        Ok(self.positions(radius, dimensions)?.collect())
+    }
+
+    /// Returns the positions of the tiles of the shape, without storing them.
+    pub(crate) fn positions(
+        self,
+        radius: Option<u16>,
+        dimensions: Option<(u16, u16)>,
+    ) -> Result<Box<dyn Iterator<Item = Hex>>, MazeBuilderError> {
+        if self.uses_radius() {
+            let radius = radius.ok_or(MazeBuilderError::NoRadius)?;
+            return Ok(self.radial_positions(radius));
+        }
+
+        let (width, height) = dimensions.ok_or(MazeBuilderError::NoDimensions)?;
+        if width == 0 || height == 0 {
+            return Ok(Box::new(std::iter::empty()));
+        }
+        // Center the shape on the origin, leaning towards negative coordinates
+        let left = -(i32::from(width) - 1) / 2;
+        let top = -(i32::from(height) - 1) / 2;
+        let bounds = [
+            left,
+            left + i32::from(width) - 1,
+            top,
+            top + i32::from(height) - 1,
+        ];
+
+        Ok(match self {
+            Self::FlatRectangle => Box::new(shapes::flat_rectangle(bounds)),
+            Self::PointyRectangle => Box::new(shapes::pointy_rectangle(bounds)),
+            _ => Box::new(shapes::parallelogram(
+                Hex::new(bounds[0], bounds[2]),
+                Hex::new(bounds[1], bounds[3]),
+            )),
+        })
+    }
+
+    fn radial_positions(self, radius: u16) -> Box<dyn Iterator<Item = Hex>> {
+        let hex_radius = u32::from(radius);
+        match self {
+            Self::Triangle => Box::new(shapes::triangle(hex_radius)),
+            Self::Ring { width } => {
+                let inner = (hex_radius + 1).saturating_sub(u32::from(width));
+                Box::new(Hex::ZERO.rings(inner..=hex_radius).flatten())
+            }
+            Self::Spiral { arm_width } => {
+                let pitch = u32::from(arm_width.max(1)) + 1;
+                Box::new(
+                    std::iter::once(Hex::ZERO).chain((1..=hex_radius).flat_map(move |ring| {
+                        Hex::ZERO
+                            .ring(ring)
+                            .enumerate()
+                            .filter(move |&(index, _)| !is_spiral_wall(ring, index, pitch))
+                            .map(|(_, hex)| hex)
+                    })),
+                )
+            }
+            _ => Box::new(shapes::hexagon(Hex::ZERO, hex_radius)),
+        }
+    }
+}
+
+/// Returns `true` if the tile at `index` of `ring` lies on the wall of a spiral.
+///
+/// Going around the ring, the wall moves out by one ring for each `1 / pitch` of a turn, so it
+/// meets the next turn of itself `pitch` rings further out.
+#[allow(clippy::cast_possible_truncation)]
+fn is_spiral_wall(ring: u32, index: usize, pitch: u32) -> bool {
+    let ring_len = 6 * u64::from(ring);
+    let turn_offset = (index as u64 * u64::from(pitch) / ring_len) as u32;
+    ring % pitch == turn_offset
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::builder::create_hex_maze;
+    use claims::assert_ok;
+    use hexx::EdgeDirection;
+    use rstest::rstest;
+    use std::collections::HashSet;
+
+    /// Returns `true` if all tiles can be reached from each other, ignoring walls.
+    fn is_contiguous(maze: &Maze) -> bool {
+        let Some(&start) = maze.keys().next() else {
+            return true;
+        };
+        let mut to_visit = vec![start];
+        let mut connected = HashSet::new();
+        while let Some(current) = to_visit.pop() {
+            if !connected.insert(current) {
+                continue;
+            }
+            for direction in EdgeDirection::ALL_DIRECTIONS {
+                let neighbor = current + direction;
+                if maze.get(&neighbor).is_some() {
+                    to_visit.push(neighbor);
+                }
+            }
+        }
+        connected.len() == maze.count()
+    }
+
+    #[rstest]
+    #[case(MazeShape::FlatRectangle, 6, 4, 24)]
+    #[case(MazeShape::PointyRectangle, 6, 4, 24)]
+    #[case(MazeShape::Parallelogram, 6, 4, 24)]
+    #[case(MazeShape::FlatRectangle, 1, 1, 1)]
+    #[case(MazeShape::Parallelogram, 0, 4, 0)]
+    fn dimension_shapes(
+        #[case] shape: MazeShape,
+        #[case] width: u16,
+        #[case] height: u16,
+        #[case] expected: usize,
+    ) {
+        let maze = assert_ok!(shape.create_maze(None, Some((width, height))));
+        assert_eq!(maze.count(), expected);
+        assert!(is_contiguous(&maze));
+        if expected > 0 {
+            assert!(maze.get(&Hex::ZERO).is_some(), "Shape should be centered");
+        }
+    }
+
+    #[rstest]
+    #[case(MazeShape::Hexagon, 3, 37)]
+    #[case(MazeShape::Triangle, 3, 10)]
+    #[case(MazeShape::Ring { width: 1 }, 3, 18)]
+    #[case(MazeShape::Ring { width: 2 }, 3, 30)]
+    #[case(MazeShape::Ring { width: 10 }, 3, 37)]
+    fn radius_shapes(#[case] shape: MazeShape, #[case] radius: u16, #[case] expected: usize) {
+        let maze = assert_ok!(shape.create_maze(Some(radius), None));
+        assert_eq!(maze.count(), expected);
+        assert!(is_contiguous(&maze));
+    }
+
+    #[rstest]
+    #[case(0, 10)]
+    #[case(1, 10)]
+    #[case(2, 10)]
+    #[case(3, 25)]
+    #[case(5, 40)]
+    fn spiral_is_contiguous(#[case] arm_width: u16, #[case] radius: u16) {
+        let maze = assert_ok!(MazeShape::Spiral { arm_width }.create_maze(Some(radius), None));
+        let hexagon = create_hex_maze(radius);
+
+        assert!(maze.count() < hexagon.count(), "Spiral should have a wall");
+        assert!(maze.get(&Hex::ZERO).is_some());
+        assert!(is_contiguous(&maze));
+    }
+
+    #[rstest]
+    #[case(MazeShape::Hexagon, MazeBuilderError::NoRadius)]
+    #[case(MazeShape::Spiral { arm_width: 2 }, MazeBuilderError::NoRadius)]
+    #[case(MazeShape::FlatRectangle, MazeBuilderError::NoDimensions)]
+    #[case(MazeShape::Parallelogram, MazeBuilderError::NoDimensions)]
+    fn missing_size(#[case] shape: MazeShape, #[case] error: MazeBuilderError) {
+        assert_eq!(shape.create_maze(None, None), Err(error));
+    }
+}
@@ -4,25 +4,48 @@ use crate::traits::WorldPositionable;
 use crate::traits::{TilePosition, WallStorage};
 #[cfg(feature = "bevy")]
 use bevy::prelude::*;
-use hexx::Hex;
 #[cfg(feature = "bevy_reflect")]
 use hexx::HexLayout;
+use hexx::{EdgeDirection, Hex};
 use std::fmt::Display;
 
 /// Represents a single hexagonal tile in the maze
 ///
-/// Each tile has a position and a set of walls defining its boundaries.
+/// Each tile has a position and a set of walls defining its boundaries. In weave mazes, a tile
+/// may also have an under-passage, a corridor tunneling beneath it along one axis.
+///
+/// Tiles carry user data of type `T`, such as terrain or loot, which defaults to `()`.
+///
+/// # Examples
+///
+/// ```
+/// use hexlab::prelude::*;
+///
+/// let mut tile = Tile::with_data(Hex::ZERO, "grass");
+/// assert_eq!(*tile.data(), "grass");
+///
+/// *tile.data_mut() = "sand";
+/// let tile = tile.map_data(str::len);
+/// assert_eq!(*tile.data(), 4);
+/// ```
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[cfg_attr(feature = "bevy_reflect", derive(bevy_reflect::Reflect))]
 #[cfg_attr(feature = "bevy", derive(Component))]
 #[cfg_attr(feature = "bevy", reflect(Component))]
 #[derive(Debug, Clone, Default, PartialEq, Eq)]
-pub struct Tile {
+pub struct Tile<T = ()> {
     pub(crate) pos: Hex,
     pub(crate) walls: Walls,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub(crate) under: Option<EdgeDirection>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, bound(deserialize = "T: serde::Deserialize<'de> + Default"))
+    )]
+    pub(crate) data: T,
 }
 
-impl TilePosition for Tile {
+impl<T> TilePosition for Tile<T> {
     /// Returns position of the tile
     ///
     /// # Examples
@@ -39,7 +62,7 @@ impl TilePosition for Tile {
     }
 }
 
-impl WallStorage for Tile {
+impl<T> WallStorage for Tile<T> {
     /// Returns an immutable reference to the tile's walls
     ///
     /// # Examples
@@ -72,7 +95,7 @@ impl WallStorage for Tile {
 }
 
 #[cfg(feature = "bevy_reflect")]
-impl WorldPositionable for Tile {
+impl<T> WorldPositionable for Tile<T> {
     /// Converts the tile's position to a 2D vector based on the given layout.
     ///
     /// # Arguments
@@ -113,23 +136,102 @@ impl Tile {
     /// ```
     #[must_use]
     pub fn new(pos: Hex) -> Self {
+        Self::with_data(pos, ())
+    }
+}
+
+impl<T> Tile<T> {
+    /// Creates a new tile with the given position, default walls and user data.
+    ///
+    /// # Arguments
+    ///
+    /// - `pos` - The hexagonal coordinates of the tile.
+    /// - `data` - The user data attached to the tile.
+    #[must_use]
+    pub fn with_data(pos: Hex, data: T) -> Self {
         Self {
             pos,
             walls: Walls::default(),
+            under: None,
+            data,
+        }
+    }
+
+    /// Returns a reference to the user data attached to the tile.
+    #[inline]
+    #[must_use]
+    pub const fn data(&self) -> &T {
+        &self.data
+    }
+
+    /// Returns a mutable reference to the user data attached to the tile.
+    #[inline]
+    #[must_use]
+    pub const fn data_mut(&mut self) -> &mut T {
+        &mut self.data
+    }
+
+    /// Replaces the user data of the tile with the result of `f`, keeping its position,
+    /// walls and under-passage.
+    #[must_use]
+    pub fn map_data<U>(self, f: impl FnOnce(T) -> U) -> Tile<U> {
+        Tile {
+            pos: self.pos,
+            walls: self.walls,
+            under: self.under,
+            data: f(self.data),
         }
     }
+
+    /// Returns the direction of the under-passage beneath the tile, if any.
+    ///
+    /// The under-passage runs straight through the tile in both this direction and the
+    /// opposite one, connecting the two neighbors on that axis without entering the tile. The
+    /// tile's own walls on that axis stay in place, as they belong to the passage on top.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use hexlab::prelude::*;
+    ///
+    /// let mut tile = Tile::new(Hex::ZERO);
+    /// assert_eq!(tile.under_passage(), None);
+    ///
+    /// tile.set_under_passage(Some(EdgeDirection::FLAT_NORTH));
+    /// assert!(tile.has_under_passage(EdgeDirection::FLAT_SOUTH));
+    /// assert!(!tile.has_under_passage(EdgeDirection::FLAT_NORTH_EAST));
+    /// ```
+    #[inline]
+    #[must_use]
+    pub const fn under_passage(&self) -> Option<EdgeDirection> {
+        self.under
+    }
+
+    /// Sets or removes the under-passage beneath the tile.
+    ///
+    /// Only marks the tile, the walls of the neighbors connected by the under-passage are left
+    /// untouched.
+    #[inline]
+    pub const fn set_under_passage(&mut self, direction: Option<EdgeDirection>) {
+        self.under = direction;
+    }
+
+    /// Returns `true` if an under-passage runs beneath the tile along the axis of `direction`.
+    #[inline]
+    #[must_use]
+    pub fn has_under_passage(&self, direction: EdgeDirection) -> bool {
+        self.under
+            .is_some_and(|under| under == direction || under == direction.const_neg())
+    }
 }
 
 impl From<Hex> for Tile {
     fn from(value: Hex) -> Self {
-        Self {
-            pos: value,
-            walls: Walls::default(),
-        }
+        Self::new(value)
     }
 }
 
-impl Display for Tile {
+impl<T> Display for Tile<T> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "({},{})", self.pos.x, self.pos.y)
     }
@@ -138,7 +240,6 @@ impl Display for Tile {
 #[cfg(test)]
 mod test {
     use super::*;
-    use hexx::EdgeDirection;
     use rand::{thread_rng, Rng};
 
     fn random_hex() -> Hex {
@@ -236,7 +337,7 @@ mod test {
             let layout = HexLayout::default();
             let tile = Tile::new(Hex::new(1, 0));
             let vec2 = tile.to_vec2(&layout);
-            assert_eq!(vec2, Vec2::new(1.5, -0.8660254));
+            assert_eq!(vec2, Vec2::new(1.5, -0.866_025_4));
         }
 
         #[test]
@@ -244,7 +345,7 @@ mod test {
             let layout = HexLayout::default();
             let tile = Tile::new(Hex::new(0, 1));
             let vec3 = tile.to_vec3(&layout);
-            assert_eq!(vec3, Vec3::new(0.0, 0.0, -1.7320508));
+            assert_eq!(vec3, Vec3::new(0.0, 0.0, -1.732_050_8));
         }
     }
 }
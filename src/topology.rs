@@ -0,0 +1,152 @@
+use hexx::Hex;
+
+/// How the edges of a maze connect, used to look up the neighbors of its tiles.
+///
+/// Wrapping topologies join every edge of the board to the opposite one, so leaving the maze
+/// on one side enters it again on the other. Their sizes match the tiles created by the
+/// [`MazeShape`] of the same name, placed around [`Hex::ZERO`].
+///
+/// [`MazeShape`]: crate::MazeShape
+///
+/// # Examples
+///
+/// ```
+/// use hexlab::prelude::*;
+///
+/// let topology = Topology::Hexagon { radius: 2 };
+/// // Moving off one edge of the hexagon enters it again on the opposite one
+/// assert_eq!(topology.wrap(Hex::new(0, -3)), Hex::new(2, 0));
+/// assert_eq!(topology.wrap(Hex::new(1, -1)), Hex::new(1, -1));
+/// ```
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "bevy_reflect", derive(bevy_reflect::Reflect))]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Topology {
+    /// The edges of the maze do not connect to anything.
+    #[default]
+    Bounded,
+    /// A hexagon of the given radius, where each of the six edges wraps to the opposite one.
+    Hexagon { radius: u16 },
+    /// A rectangle of flat topped hexagons, wrapping both horizontally and vertically.
+    ///
+    /// The `width` must be even for the columns to line up across the seam.
+    FlatRectangle { width: u16, height: u16 },
+    /// A rectangle of pointy topped hexagons, wrapping both horizontally and vertically.
+    ///
+    /// The `height` must be even for the rows to line up across the seam.
+    PointyRectangle { width: u16, height: u16 },
+    /// A parallelogram along the `x` and `y` axes, wrapping along both of them.
+    Parallelogram { width: u16, height: u16 },
+}
+
+impl Topology {
+    /// Returns `true` if the edges of the maze wrap around.
+    #[inline]
+    #[must_use]
+    pub const fn is_wrapping(self) -> bool {
+        !matches!(self, Self::Bounded)
+    }
+
+    /// Maps any position onto the board, wrapping it around the edges.
+    ///
+    /// Positions are returned unchanged by [`Topology::Bounded`], or if the board is empty.
+    #[must_use]
+    pub fn wrap(self, hex: Hex) -> Hex {
+        match self {
+            Self::Bounded => hex,
+            Self::Hexagon { radius } => hex.wrap_in_range(u32::from(radius)),
+            Self::FlatRectangle { width, height } => {
+                let Some([col, row]) = wrap_offset([hex.x, hex.y + (hex.x >> 1)], width, height)
+                else {
+                    return hex;
+                };
+                Hex::new(col, row - (col >> 1))
+            }
+            Self::PointyRectangle { width, height } => {
+                let Some([col, row]) = wrap_offset([hex.x + (hex.y >> 1), hex.y], width, height)
+                else {
+                    return hex;
+                };
+                Hex::new(col - (row >> 1), row)
+            }
+            Self::Parallelogram { width, height } => {
+                wrap_offset([hex.x, hex.y], width, height).map_or(hex, |[x, y]| Hex::new(x, y))
+            }
+        }
+    }
+
+    /// Checks that the seams of the topology line up, returning the reason if they do not.
+    pub(crate) fn validate(self) -> Result<(), String> {
+        match self {
+            Self::FlatRectangle { width, .. } if width % 2 != 0 => Err(format!(
+                "a flat rectangle must have an even width to wrap, got {width}"
+            )),
+            Self::PointyRectangle { height, .. } if height % 2 != 0 => Err(format!(
+                "a pointy rectangle must have an even height to wrap, got {height}"
+            )),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Wraps offset coordinates into a `width` by `height` area centered on the origin, the same
+/// way [`MazeShape`] places rectangular shapes.
+///
+/// [`MazeShape`]: crate::MazeShape
+fn wrap_offset([col, row]: [i32; 2], width: u16, height: u16) -> Option<[i32; 2]> {
+    if width == 0 || height == 0 {
+        return None;
+    }
+    let (width, height) = (i32::from(width), i32::from(height));
+    let left = -(width - 1) / 2;
+    let top = -(height - 1) / 2;
+    Some([
+        left + (col - left).rem_euclid(width),
+        top + (row - top).rem_euclid(height),
+    ])
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::MazeShape;
+    use claims::assert_ok;
+    use hexx::EdgeDirection;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(MazeShape::Hexagon, Topology::Hexagon { radius: 4 })]
+    #[case(MazeShape::FlatRectangle, Topology::FlatRectangle { width: 8, height: 5 })]
+    #[case(MazeShape::PointyRectangle, Topology::PointyRectangle { width: 7, height: 6 })]
+    #[case(MazeShape::Parallelogram, Topology::Parallelogram { width: 7, height: 5 })]
+    fn wrap_matches_shape(#[case] shape: MazeShape, #[case] topology: Topology) {
+        let (width, height) = match topology {
+            Topology::FlatRectangle { width, height }
+            | Topology::PointyRectangle { width, height }
+            | Topology::Parallelogram { width, height } => (width, height),
+            _ => (0, 0),
+        };
+        let maze = assert_ok!(shape.create_maze(Some(4), Some((width, height))));
+        assert!(topology.validate().is_ok());
+
+        for &hex in maze.keys() {
+            assert_eq!(topology.wrap(hex), hex, "Tiles on the board stay in place");
+            for direction in EdgeDirection::ALL_DIRECTIONS {
+                let neighbor = topology.wrap(hex + direction);
+                assert!(maze.get(&neighbor).is_some(), "{hex:?} wraps off the board");
+                assert_eq!(
+                    topology.wrap(neighbor + direction.const_neg()),
+                    hex,
+                    "Wrapping should be symmetric"
+                );
+            }
+        }
+    }
+
+    #[rstest]
+    #[case(Topology::FlatRectangle { width: 7, height: 4 })]
+    #[case(Topology::PointyRectangle { width: 4, height: 7 })]
+    fn validate_odd_seam(#[case] topology: Topology) {
+        assert!(topology.validate().is_err());
+    }
+}
@@ -1,5 +1,6 @@
-use crate::Walls;
-use hexx::Hex;
+use crate::{errors::MazeError, generator::diff_steps, GenerationStep, Maze, Walls};
+use hexx::{EdgeDirection, Hex};
+use rand::RngCore;
 
 pub trait TilePosition {
     /// Returns position of the tile
@@ -20,3 +21,127 @@ pub trait WallStorage {
     fn walls(&self) -> &Walls;
     fn walls_mut(&mut self) -> &mut Walls;
 }
+
+/// Storage of the tiles and walls of a maze, shared by [`Maze`] and [`DenseMaze`].
+///
+/// Generators written against this trait run on either storage, see
+/// [`MazeBuilder::build_dense`].
+///
+/// [`DenseMaze`]: crate::DenseMaze
+/// [`MazeBuilder::build_dense`]: crate::MazeBuilder::build_dense
+pub trait MazeStorage {
+    /// Returns the number of tiles in the maze.
+    #[must_use]
+    fn count(&self) -> usize;
+
+    /// Returns a reference to the walls of the tile at the specified coordinates.
+    #[must_use]
+    fn get_walls(&self, coord: &Hex) -> Option<&Walls>;
+
+    /// Returns the position of the tile next to `pos` in `direction`, wrapping around the
+    /// edges of the maze according to its topology.
+    #[must_use]
+    fn neighbor(&self, pos: Hex, direction: EdgeDirection) -> Option<Hex>;
+
+    /// Removes the wall between a tile and its neighbor in `direction`, on both sides.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`MazeError::InvalidCoordinate`] if there is no tile at `coord`.
+    /// Returns [`MazeError::NoNeighbor`] if there is no tile in `direction`.
+    fn carve(&mut self, coord: &Hex, direction: EdgeDirection) -> Result<bool, MazeError>;
+
+    /// Adds the wall between a tile and its neighbor in `direction`, on both sides.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`MazeError::InvalidCoordinate`] if there is no tile at `coord`.
+    /// Returns [`MazeError::NoNeighbor`] if there is no tile in `direction`.
+    fn wall_up(&mut self, coord: &Hex, direction: EdgeDirection) -> Result<bool, MazeError>;
+}
+
+/// A maze generation algorithm.
+///
+/// Implemented by [`GeneratorType`] for the built-in algorithms. Custom algorithms can be used
+/// with [`MazeBuilder::with_custom_generator`], which validates the start position and seeds the
+/// random number generator the same way as for the built-in ones.
+///
+/// [`GeneratorType`]: crate::GeneratorType
+/// [`MazeBuilder::with_custom_generator`]: crate::MazeBuilder::with_custom_generator
+///
+/// # Examples
+///
+/// ```
+/// use hexlab::prelude::*;
+/// use rand::RngCore;
+///
+/// /// Connects every tile to the tile next to it, forming a single long corridor per row.
+/// struct Rows;
+///
+/// impl MazeGenerator for Rows {
+///     fn generate(&self, maze: &mut Maze, _start: Hex, _rng: &mut dyn RngCore) {
+///         let positions = maze.keys().copied().collect::<Vec<_>>();
+///         for pos in positions {
+///             // Opens both sides of the wall, doing nothing at the edge of the maze
+///             let _ = maze.carve(&pos, EdgeDirection::POINTY_EAST);
+///         }
+///     }
+/// }
+///
+/// let maze = MazeBuilder::new()
+///     .with_radius(2)
+///     .with_custom_generator(Rows)
+///     .build()
+///     .expect("Failed to create maze");
+///
+/// let walls = maze.get_walls(&Hex::ZERO).unwrap();
+/// assert!(!walls.contains(EdgeDirection::POINTY_EAST));
+/// assert!(!walls.contains(EdgeDirection::POINTY_WEST));
+/// ```
+pub trait MazeGenerator {
+    /// Generates the maze in place.
+    ///
+    /// # Arguments
+    ///
+    /// - `maze` - The maze to generate, with all walls of every tile present.
+    /// - `start` - The position to start generation from, which is a tile of the maze.
+    /// - `rng` - The random number generator to draw all randomness from.
+    fn generate(&self, maze: &mut Maze, start: Hex, rng: &mut dyn RngCore);
+
+    /// Generates the maze in place, returning the passages carved in order.
+    ///
+    /// Used by [`MazeBuilder::build_steps`] to replay the generation after it has completed.
+    /// Replaying the returned steps on the original maze must produce the generated maze.
+    ///
+    /// The default implementation compares the maze before and after [`generate`], so the
+    /// steps are reported in tile order rather than in the order they were carved. Generators
+    /// should override it to report the actual order.
+    ///
+    /// [`MazeBuilder::build_steps`]: crate::MazeBuilder::build_steps
+    /// [`generate`]: MazeGenerator::generate
+    fn generate_steps(
+        &self,
+        maze: &mut Maze,
+        start: Hex,
+        rng: &mut dyn RngCore,
+    ) -> Vec<GenerationStep> {
+        let before = maze.clone();
+        self.generate(maze, start, rng);
+        diff_steps(&before, maze)
+    }
+}
+
+impl<T: MazeGenerator + ?Sized> MazeGenerator for &T {
+    fn generate(&self, maze: &mut Maze, start: Hex, rng: &mut dyn RngCore) {
+        (**self).generate(maze, start, rng);
+    }
+
+    fn generate_steps(
+        &self,
+        maze: &mut Maze,
+        start: Hex,
+        rng: &mut dyn RngCore,
+    ) -> Vec<GenerationStep> {
+        (**self).generate_steps(maze, start, rng)
+    }
+}
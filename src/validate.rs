@@ -0,0 +1,143 @@
+//! Consistency checks for mazes loaded from disk or edited by hand.
+//!
+//! Generators always keep both sides of a wall in sync, but a maze deserialized from a file
+//! or edited through the tile map directly may not be. [`Maze::validate`] lists the problems
+//! it finds, and [`Maze::repair`] fixes them.
+use crate::{generator::sorted_tiles, Maze};
+use hexx::{EdgeDirection, Hex};
+
+/// Problems found in a maze by [`Maze::validate`].
+///
+/// Every list is sorted by position and direction.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ValidationReport {
+    /// Walls present on one side of an edge between two tiles but missing on the other,
+    /// listed from the walled side.
+    pub asymmetric_walls: Vec<(Hex, EdgeDirection)>,
+    /// Missing walls facing a position without a tile.
+    pub open_edges: Vec<(Hex, EdgeDirection)>,
+    /// Tiles stored under a different position than their own, as the position they are
+    /// stored under and the position of the tile.
+    pub misplaced_tiles: Vec<(Hex, Hex)>,
+}
+
+impl ValidationReport {
+    /// Returns `true` if no problems were found.
+    #[must_use]
+    pub const fn is_valid(&self) -> bool {
+        self.asymmetric_walls.is_empty()
+            && self.open_edges.is_empty()
+            && self.misplaced_tiles.is_empty()
+    }
+}
+
+/// Decides how [`Maze::repair`] resolves a wall present on only one side of an edge.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "bevy_reflect", derive(bevy_reflect::Reflect))]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum RepairPolicy {
+    /// Add the missing wall, closing the passage.
+    #[default]
+    Close,
+    /// Remove the remaining wall, opening the passage.
+    Open,
+}
+
+impl<T> Maze<T> {
+    /// Checks that the walls on both sides of every edge agree, that the maze is enclosed
+    /// and that every tile is stored under its own position.
+    ///
+    /// Edges are found according to the [`Topology`] of the maze. The walls around a weave
+    /// crossing are expected to differ: the crossed tile keeps the walls along its
+    /// under-passage, while the tunnel ends are open towards it.
+    ///
+    /// [`Topology`]: crate::Topology
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use hexlab::prelude::*;
+    ///
+    /// let neighbor = Hex::ZERO + EdgeDirection::FLAT_NORTH;
+    /// let mut maze = Maze::from([Hex::ZERO, neighbor]);
+    /// assert!(maze.validate().is_valid());
+    ///
+    /// maze.remove_tile_wall(&neighbor, EdgeDirection::FLAT_SOUTH).unwrap();
+    /// assert_eq!(
+    ///     maze.validate().asymmetric_walls,
+    ///     vec![(Hex::ZERO, EdgeDirection::FLAT_NORTH)]
+    /// );
+    /// ```
+    #[must_use]
+    pub fn validate(&self) -> ValidationReport {
+        let positions = sorted_tiles(self.keys().copied());
+
+        let mut report = ValidationReport::default();
+        for pos in positions {
+            let Some(tile) = self.get(&pos) else {
+                continue;
+            };
+            if tile.pos != pos {
+                report.misplaced_tiles.push((pos, tile.pos));
+            }
+
+            for direction in EdgeDirection::ALL_DIRECTIONS {
+                let walled = tile.walls.contains(direction);
+                let Some(neighbor) = self.neighbor(pos, direction) else {
+                    if !walled {
+                        report.open_edges.push((pos, direction));
+                    }
+                    continue;
+                };
+                let Some(other) = self.get(&neighbor) else {
+                    continue;
+                };
+                let crossing =
+                    tile.has_under_passage(direction) || other.has_under_passage(direction);
+                if walled && !crossing && !other.walls.contains(direction.const_neg()) {
+                    report.asymmetric_walls.push((pos, direction));
+                }
+            }
+        }
+        report
+    }
+
+    /// Fixes the problems [`Maze::validate`] finds, returning the report of what was fixed.
+    ///
+    /// Asymmetric walls are resolved according to `policy`, open edges are walled up, and
+    /// misplaced tiles take the position they are stored under.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use hexlab::prelude::*;
+    ///
+    /// let neighbor = Hex::ZERO + EdgeDirection::FLAT_NORTH;
+    /// let mut maze = Maze::from([Hex::ZERO, neighbor]);
+    /// maze.remove_tile_wall(&neighbor, EdgeDirection::FLAT_SOUTH).unwrap();
+    ///
+    /// let report = maze.repair(RepairPolicy::Open);
+    /// assert_eq!(report.asymmetric_walls.len(), 1);
+    /// assert!(maze.validate().is_valid());
+    /// assert!(!maze.get_walls(&Hex::ZERO).unwrap().contains(EdgeDirection::FLAT_NORTH));
+    /// ```
+    pub fn repair(&mut self, policy: RepairPolicy) -> ValidationReport {
+        let report = self.validate();
+
+        for &(pos, _) in &report.misplaced_tiles {
+            if let Some(tile) = self.get_mut(&pos) {
+                tile.pos = pos;
+            }
+        }
+        for &(pos, direction) in &report.open_edges {
+            let _ = self.add_tile_wall(&pos, direction);
+        }
+        for &(pos, direction) in &report.asymmetric_walls {
+            let _ = match policy {
+                RepairPolicy::Close => self.wall_up(&pos, direction),
+                RepairPolicy::Open => self.carve(&pos, direction),
+            };
+        }
+        report
+    }
+}
@@ -1,4 +1,7 @@
+mod common;
+
 use claims::{assert_err, assert_gt, assert_matches, assert_ok, assert_some};
+use common::{assert_layered_spanning_tree, assert_spanning_tree, connected_tiles};
 use hexlab::prelude::*;
 use rstest::rstest;
 
@@ -64,42 +67,35 @@ fn different_seeds_produce_different_mazes() {
 
 #[test]
 fn maze_connectivity() {
-    let maze = assert_ok!(MazeBuilder::new().with_radius(3).build());
-
     // Helper function to count accessible neighbors
     fn count_accessible_neighbors(maze: &Maze, pos: Hex) -> usize {
         hexx::EdgeDirection::ALL_DIRECTIONS
             .iter()
             .filter(|&&dir| {
                 let neighbor = pos + dir;
-                if let Some(walls) = maze.get_walls(&pos) {
-                    !walls.contains(dir) && maze.get(&neighbor).is_some()
-                } else {
-                    false
-                }
+                maze.get_walls(&pos)
+                    .is_some_and(|walls| !walls.contains(dir) && maze.get(&neighbor).is_some())
             })
             .count()
     }
 
+    let maze = assert_ok!(MazeBuilder::new().with_radius(3).build());
+
     // Check that each tile has at least one connection
     for &pos in maze.keys() {
         let accessible_neighbors = count_accessible_neighbors(&maze, pos);
         claims::assert_gt!(
             accessible_neighbors,
             0,
-            "Tile at {:?} has no accessible neighbors",
-            pos
+            "Tile at {pos:?} has no accessible neighbors"
         );
     }
 }
 
 #[test]
 fn maze_boundaries() {
+    let maze = assert_ok!(MazeBuilder::new().with_radius(3).build());
     let radius = 3;
-    let maze = MazeBuilder::new()
-        .with_radius(radius as u16)
-        .build()
-        .unwrap();
 
     // Test that tiles exist within the radius
     for q in -radius..=radius {
@@ -108,8 +104,7 @@ fn maze_boundaries() {
             if q.abs() + r.abs() <= radius {
                 assert!(
                     maze.get(&pos).is_some(),
-                    "Expected tile at {:?} to exist",
-                    pos
+                    "Expected tile at {pos:?} to exist"
                 );
             }
         }
@@ -118,9 +113,15 @@ fn maze_boundaries() {
 
 #[rstest]
 #[case(GeneratorType::RecursiveBacktracking)]
+#[case(GeneratorType::Prims)]
+#[case(GeneratorType::Kruskal)]
+#[case(GeneratorType::Wilson)]
+#[case(GeneratorType::AldousBroder)]
+#[case(GeneratorType::AldousBroderWilson { switch_at: 50 })]
+#[case(GeneratorType::GrowingTree { policy: GrowingTreePolicy::Mixed { newest: 75 } })]
+#[case(GeneratorType::HuntAndKill)]
+#[case(GeneratorType::RecursiveDivision)]
 fn generate_maze_with_different_types(#[case] generator: GeneratorType) {
-    // TODO: Add more generator types when they become available
-
     let maze = assert_ok!(MazeBuilder::new()
         .with_radius(3)
         .with_generator(generator)
@@ -128,3 +129,547 @@ fn generate_maze_with_different_types(#[case] generator: GeneratorType) {
 
     assert_gt!(maze.count(), 0);
 }
+
+/// Opens the wall in a random direction of the start tile.
+struct OpenStart;
+
+impl MazeGenerator for OpenStart {
+    fn generate(&self, maze: &mut Maze, start: Hex, rng: &mut dyn rand::RngCore) {
+        let index = assert_ok!(usize::try_from(rng.next_u32())) % 6;
+        let _ = maze.remove_tile_wall(&start, EdgeDirection::ALL_DIRECTIONS[index]);
+    }
+}
+
+#[test]
+fn custom_generator() {
+    let start = Hex::new(1, -1);
+    let maze = assert_ok!(MazeBuilder::new()
+        .with_radius(3)
+        .with_start_position(start)
+        .with_custom_generator(OpenStart)
+        .build());
+
+    for (&pos, tile) in maze.iter() {
+        let expected_walls = if pos == start { 5 } else { 6 };
+        assert_eq!(tile.walls().count(), expected_walls);
+    }
+}
+
+#[test]
+fn custom_generator_with_seed() {
+    let build = || {
+        MazeBuilder::new()
+            .with_radius(3)
+            .with_seed(12345)
+            .with_custom_generator(OpenStart)
+            .build()
+    };
+
+    assert_eq!(assert_ok!(build()), assert_ok!(build()));
+}
+
+#[test]
+fn custom_generator_invalid_start_position() {
+    let maze = MazeBuilder::new()
+        .with_radius(3)
+        .with_start_position(Hex::new(10, 10))
+        .with_custom_generator(OpenStart)
+        .build();
+
+    assert_matches!(maze, Err(MazeBuilderError::InvalidStartPosition(_)));
+}
+
+#[rstest]
+#[case(0.0)]
+#[case(0.5)]
+#[case(1.0)]
+fn braid_removes_dead_ends(#[case] fraction: f32) {
+    let build = |braid| {
+        let builder = MazeBuilder::new().with_radius(6).with_seed(12345);
+        match braid {
+            Some(fraction) => builder.with_braid(fraction),
+            None => builder,
+        }
+        .build()
+    };
+    let perfect = assert_ok!(build(None));
+    let braided = assert_ok!(build(Some(fraction)));
+
+    assert_eq!(braided, assert_ok!(build(Some(fraction))));
+    assert!(braided.dead_ends().len() <= perfect.dead_ends().len());
+    if fraction <= 0.0 {
+        assert_eq!(braided, perfect);
+    }
+    if fraction >= 1.0 {
+        assert!(braided.dead_ends().is_empty());
+    }
+}
+
+#[rstest]
+#[case(MazeShape::FlatRectangle)]
+#[case(MazeShape::PointyRectangle)]
+#[case(MazeShape::Parallelogram)]
+#[case(MazeShape::Triangle)]
+#[case(MazeShape::Ring { width: 2 })]
+#[case(MazeShape::Spiral { arm_width: 2 })]
+fn shaped_maze_is_perfect(
+    #[case] shape: MazeShape,
+    #[values(
+        GeneratorType::RecursiveBacktracking,
+        GeneratorType::Kruskal,
+        GeneratorType::Wilson
+    )]
+    generator: GeneratorType,
+) {
+    let maze = assert_ok!(MazeBuilder::new()
+        .with_shape(shape)
+        .with_radius(6)
+        .with_dimensions(12, 7)
+        .with_seed(12345)
+        .with_generator(generator)
+        .build());
+
+    assert_spanning_tree(&maze);
+}
+
+#[test]
+fn shaped_maze_missing_dimensions() {
+    let result = MazeBuilder::new()
+        .with_shape(MazeShape::FlatRectangle)
+        .with_radius(5)
+        .build();
+    assert_matches!(result, Err(MazeBuilderError::NoDimensions));
+}
+
+#[test]
+fn shaped_maze_invalid_start_position() {
+    // The ring leaves out the center
+    let result = MazeBuilder::new()
+        .with_shape(MazeShape::Ring { width: 1 })
+        .with_radius(3)
+        .with_start_position(Hex::ZERO)
+        .build();
+    assert_matches!(result, Err(MazeBuilderError::InvalidStartPosition(_)));
+}
+
+#[rstest]
+fn masked_maze_is_restricted_to_tiles(
+    #[values(
+        GeneratorType::RecursiveBacktracking,
+        GeneratorType::Prims,
+        GeneratorType::Kruskal,
+        GeneratorType::Wilson,
+        GeneratorType::AldousBroder,
+        GeneratorType::HuntAndKill,
+        GeneratorType::RecursiveDivision
+    )]
+    generator: GeneratorType,
+) {
+    // An L-shaped corridor, two tiles wide, that does not contain the origin
+    let tiles = (2..12)
+        .flat_map(|x| [Hex::new(x, 0), Hex::new(x, 1)])
+        .chain((2..8).flat_map(|y| [Hex::new(2, y), Hex::new(3, y)]))
+        .collect::<Vec<_>>();
+    let maze = assert_ok!(MazeBuilder::new()
+        .with_tiles(tiles.clone())
+        .with_seed(12345)
+        .with_generator(generator)
+        .build());
+
+    assert!(tiles.iter().all(|hex| maze.get(hex).is_some()));
+    assert_eq!(maze.count(), 32);
+
+    let passages = maze
+        .iter()
+        .flat_map(|(&pos, tile)| {
+            EdgeDirection::ALL_DIRECTIONS
+                .into_iter()
+                .filter(move |&direction| !tile.walls().contains(direction))
+                .map(move |direction| pos + direction)
+        })
+        .collect::<Vec<_>>();
+    assert!(
+        passages.iter().all(|hex| maze.get(hex).is_some()),
+        "Passages should only lead to masked tiles"
+    );
+    assert_eq!(passages.len() / 2, maze.count() - 1);
+}
+
+#[test]
+fn masked_maze_disconnected_tiles() {
+    let result = MazeBuilder::new()
+        .with_tiles([
+            Hex::new(0, 0),
+            Hex::new(1, 0),
+            Hex::new(5, 5),
+            Hex::new(-5, 0),
+            Hex::new(-5, 1),
+            Hex::new(-5, 2),
+        ])
+        .build();
+
+    assert_eq!(
+        result,
+        Err(MazeBuilderError::DisconnectedTiles(vec![
+            vec![Hex::new(-5, 0), Hex::new(-5, 1), Hex::new(-5, 2)],
+            vec![Hex::new(0, 0), Hex::new(1, 0)],
+            vec![Hex::new(5, 5)],
+        ]))
+    );
+}
+
+#[test]
+fn masked_maze_ignores_radius() {
+    let maze = assert_ok!(MazeBuilder::new()
+        .with_radius(10)
+        .with_tiles([Hex::ZERO, Hex::new(1, 0)])
+        .build());
+    assert_eq!(maze.count(), 2);
+}
+
+#[rstest]
+fn maze_with_holes_routes_around_them(
+    #[values(
+        GeneratorType::RecursiveBacktracking,
+        GeneratorType::Prims,
+        GeneratorType::Kruskal,
+        GeneratorType::Wilson,
+        GeneratorType::HuntAndKill,
+        GeneratorType::RecursiveDivision
+    )]
+    generator: GeneratorType,
+) {
+    // A lake next to the center and a row of pillars
+    let holes = Hex::new(2, -1)
+        .range(1)
+        .chain([Hex::new(-3, 0), Hex::new(-3, 2), Hex::new(-3, 4)])
+        .collect::<Vec<_>>();
+    let maze = assert_ok!(MazeBuilder::new()
+        .with_radius(6)
+        .with_holes(holes.clone())
+        .with_seed(12345)
+        .with_generator(generator)
+        .build());
+
+    assert_eq!(maze.count(), 127 - holes.len());
+    assert!(holes.iter().all(|hole| maze.get(hole).is_none()));
+
+    for (&pos, tile) in maze.iter() {
+        for direction in EdgeDirection::ALL_DIRECTIONS {
+            if !tile.walls().contains(direction) {
+                assert_some!(
+                    maze.get(&(pos + direction)),
+                    "Passage should not lead into a hole"
+                );
+            }
+        }
+    }
+    assert_eq!(
+        connected_tiles(&maze, Hex::ZERO).len(),
+        maze.count(),
+        "All tiles should be connected"
+    );
+}
+
+#[test]
+fn maze_with_holes_start_in_hole() {
+    let result = MazeBuilder::new()
+        .with_radius(3)
+        .with_holes([Hex::new(1, 1)])
+        .with_start_position(Hex::new(1, 1))
+        .build();
+    assert_matches!(result, Err(MazeBuilderError::InvalidStartPosition(_)));
+}
+
+#[test]
+fn maze_with_holes_unreachable_region() {
+    // A ring of holes around a tile at the edge of the maze, cutting it off
+    let result = MazeBuilder::new()
+        .with_radius(3)
+        .with_holes(Hex::new(2, 0).ring(1))
+        .build();
+
+    let Err(MazeBuilderError::GenerationError(message)) = result else {
+        panic!("Expected a generation error, got {result:?}");
+    };
+    assert!(
+        message.contains("1 tiles from Hex { x: 2, y: 0"),
+        "{message}"
+    );
+}
+
+#[rstest]
+fn maze_with_rooms_is_connected(
+    #[values(
+        GeneratorType::RecursiveBacktracking,
+        GeneratorType::Prims,
+        GeneratorType::Wilson,
+        GeneratorType::HuntAndKill,
+        GeneratorType::RecursiveDivision
+    )]
+    generator: GeneratorType,
+) {
+    let maze = assert_ok!(MazeBuilder::new()
+        .with_radius(7)
+        .with_seed(12345)
+        .with_generator(generator)
+        .with_room(Hex::new(3, -1).range(2), 3)
+        .with_room(Hex::new(-4, 2).range(1), 1)
+        .with_start_position(Hex::new(3, -1))
+        .build());
+
+    assert_eq!(
+        connected_tiles(&maze, Hex::ZERO).len(),
+        maze.count(),
+        "All tiles should be connected"
+    );
+    assert!(assert_some!(maze.get_walls(&Hex::new(3, -1))).is_empty());
+}
+
+#[rstest]
+#[case(MazeShape::Hexagon, Topology::Hexagon { radius: 4 })]
+#[case(MazeShape::FlatRectangle, Topology::FlatRectangle { width: 8, height: 5 })]
+#[case(MazeShape::PointyRectangle, Topology::PointyRectangle { width: 7, height: 6 })]
+#[case(MazeShape::Parallelogram, Topology::Parallelogram { width: 6, height: 6 })]
+fn wrapping_maze_is_perfect(
+    #[case] shape: MazeShape,
+    #[case] topology: Topology,
+    #[values(
+        GeneratorType::RecursiveBacktracking,
+        GeneratorType::Prims,
+        GeneratorType::Kruskal,
+        GeneratorType::Wilson,
+        GeneratorType::HuntAndKill
+    )]
+    generator: GeneratorType,
+) {
+    let (width, height) = match topology {
+        Topology::FlatRectangle { width, height }
+        | Topology::PointyRectangle { width, height }
+        | Topology::Parallelogram { width, height } => (width, height),
+        _ => (1, 1),
+    };
+    let maze = assert_ok!(MazeBuilder::new()
+        .with_shape(shape)
+        .with_radius(4)
+        .with_dimensions(width, height)
+        .with_topology(topology)
+        .with_generator(generator)
+        .with_seed(12345)
+        .build());
+    assert_eq!(maze.topology(), topology);
+
+    let mut passages = 0;
+    for (&pos, tile) in maze.iter() {
+        for direction in EdgeDirection::ALL_DIRECTIONS {
+            let Some(neighbor) = maze.neighbor(pos, direction) else {
+                assert!(tile.walls().contains(direction));
+                continue;
+            };
+            let open = !tile.walls().contains(direction);
+            assert_eq!(
+                open,
+                !assert_some!(maze.get_walls(&neighbor)).contains(direction.const_neg()),
+                "Walls between {pos:?} and {neighbor:?} should match"
+            );
+            passages += usize::from(open);
+        }
+    }
+    // Every passage is counted from both sides, and a spanning tree has one less than tiles
+    assert_eq!(passages / 2, maze.count() - 1);
+}
+
+#[test]
+fn maze_with_odd_seam() {
+    let result = MazeBuilder::new()
+        .with_shape(MazeShape::FlatRectangle)
+        .with_dimensions(7, 4)
+        .with_topology(Topology::FlatRectangle {
+            width: 7,
+            height: 4,
+        })
+        .build();
+    assert_matches!(result, Err(MazeBuilderError::InvalidTopology(_)));
+}
+
+#[rstest]
+fn layered_maze_is_spanning_tree(
+    #[values(MazeShape::Hexagon, MazeShape::Ring { width: 2 })] shape: MazeShape,
+    #[values(1, 2, 4)] levels: usize,
+) {
+    let maze = assert_ok!(MazeBuilder::new()
+        .with_shape(shape)
+        .with_radius(4)
+        .with_levels(levels)
+        .with_seed(12345)
+        .build_layered());
+    assert_eq!(maze.level_count(), levels);
+    assert_layered_spanning_tree(&maze);
+}
+
+#[test]
+fn layered_maze_uses_several_stairs() {
+    let maze = assert_ok!(MazeBuilder::new()
+        .with_radius(4)
+        .with_levels(2)
+        .with_seed(12345)
+        .build_layered());
+    // Stairs are passages of the tree like any other, rather than one per pair of levels
+    assert_gt!(maze.stairs().len(), 1);
+    assert!(maze
+        .levels()
+        .iter()
+        .any(|level| connected_tiles(level, Hex::ZERO).len() < level.count()));
+}
+
+#[test]
+fn layered_maze_unsupported_options() {
+    let result = MazeBuilder::new()
+        .with_radius(3)
+        .with_levels(2)
+        .with_room(Hex::ZERO.range(1), 1)
+        .build_layered();
+    assert_matches!(result, Err(MazeBuilderError::GenerationError(_)));
+}
+
+#[test]
+fn layered_maze_is_deterministic() {
+    let build = || {
+        MazeBuilder::new()
+            .with_radius(3)
+            .with_levels(3)
+            .with_seed(12345)
+            .build_layered()
+    };
+    assert_eq!(assert_ok!(build()), assert_ok!(build()));
+}
+
+#[rstest]
+fn axis_bias_favors_axis(
+    #[values(
+        GeneratorType::RecursiveBacktracking,
+        GeneratorType::Prims,
+        GeneratorType::HuntAndKill,
+        GeneratorType::GrowingTree {
+            policy: GrowingTreePolicy::Random
+        }
+    )]
+    generator: GeneratorType,
+) {
+    let axis = EdgeDirection::FLAT_NORTH;
+    let axis_passages = |bias| {
+        let maze = assert_ok!(MazeBuilder::new()
+            .with_radius(8)
+            .with_seed(12345)
+            .with_generator(generator)
+            .with_bias(bias)
+            .build());
+        maze.values()
+            .map(|tile| {
+                [axis, axis.const_neg()]
+                    .into_iter()
+                    .filter(|&direction| !tile.walls().contains(direction))
+                    .count()
+            })
+            .sum::<usize>()
+    };
+
+    assert_gt!(
+        axis_passages(DirectionBias::Axis {
+            direction: axis,
+            weight: 8.0
+        }),
+        axis_passages(DirectionBias::Uniform)
+    );
+}
+
+#[rstest]
+#[case(GeneratorType::RecursiveBacktracking, None)]
+#[case(GeneratorType::RecursiveBacktracking, Some(Topology::Hexagon { radius: 6 }))]
+#[case(GeneratorType::Prims, None)]
+#[case(GeneratorType::Kruskal, None)]
+fn dense_maze_matches_maze(#[case] generator: GeneratorType, #[case] topology: Option<Topology>) {
+    let builder = || {
+        let builder = MazeBuilder::new()
+            .with_radius(6)
+            .with_seed(12345)
+            .with_generator(generator);
+        match topology {
+            Some(topology) => builder.with_topology(topology),
+            None => builder,
+        }
+    };
+    let dense = assert_ok!(builder().build_dense());
+    let maze = assert_ok!(builder().build());
+
+    assert_eq!(dense.count(), maze.count());
+    assert_eq!(dense.topology(), maze.topology());
+    assert_eq!(Maze::from(&dense), maze);
+}
+
+#[test]
+fn dense_maze_rejects_weave() {
+    let result = MazeBuilder::new()
+        .with_radius(3)
+        .with_weave(0.5)
+        .build_dense();
+    assert_matches!(result, Err(MazeBuilderError::GenerationError(_)));
+}
+
+#[rstest]
+fn dense_maze_rejects_sparse_tiles(
+    #[values(GeneratorType::RecursiveBacktracking, GeneratorType::Prims)] generator: GeneratorType,
+) {
+    // A thin ring fills a tiny part of its bounding parallelogram
+    let result = MazeBuilder::new()
+        .with_shape(MazeShape::Ring { width: 1 })
+        .with_radius(1000)
+        .with_generator(generator)
+        .build_dense();
+    assert_matches!(result, Err(MazeBuilderError::GenerationError(_)));
+}
+
+#[rstest]
+#[case(MazeShape::Ring { width: 2 }, None)]
+#[case(MazeShape::Spiral { arm_width: 2 }, Some(Hex::new(1, -1)))]
+#[case(MazeShape::FlatRectangle, None)]
+#[case(MazeShape::Triangle, Some(Hex::new(2, 0)))]
+fn dense_maze_matches_maze_shapes(#[case] shape: MazeShape, #[case] start: Option<Hex>) {
+    let builder = || {
+        let builder = MazeBuilder::new()
+            .with_shape(shape)
+            .with_radius(6)
+            .with_dimensions(9, 7)
+            .with_seed(12345);
+        match start {
+            Some(start) => builder.with_start_position(start),
+            None => builder,
+        }
+    };
+    let dense = assert_ok!(builder().build_dense());
+    let maze = assert_ok!(builder().build());
+
+    assert_eq!(Maze::from(&dense), maze);
+}
+
+#[test]
+fn dense_maze_errors() {
+    assert_matches!(
+        MazeBuilder::new().build_dense(),
+        Err(MazeBuilderError::NoRadius)
+    );
+    assert_matches!(
+        MazeBuilder::new()
+            .with_radius(3)
+            .with_start_position(Hex::new(4, 0))
+            .build_dense(),
+        Err(MazeBuilderError::InvalidStartPosition(_))
+    );
+    assert_matches!(
+        MazeBuilder::new()
+            .with_radius(3)
+            .with_topology(Topology::Hexagon { radius: 2 })
+            .build_dense(),
+        Err(MazeBuilderError::InvalidTopology(_))
+    );
+}
@@ -0,0 +1,101 @@
+// Each test crate only uses some of the helpers
+#![allow(dead_code)]
+
+use hexlab::prelude::*;
+use std::collections::HashSet;
+
+/// Returns the tiles reachable from `start` through missing walls.
+pub fn connected_tiles(maze: &Maze, start: Hex) -> HashSet<Hex> {
+    let mut connected = HashSet::from([start]);
+    let mut to_visit = vec![start];
+    while let Some(current) = to_visit.pop() {
+        let Some(walls) = maze.get_walls(&current) else {
+            continue;
+        };
+        for direction in EdgeDirection::ALL_DIRECTIONS {
+            if walls.contains(direction) {
+                continue;
+            }
+            if let Some(neighbor) = maze.neighbor(current, direction) {
+                if connected.insert(neighbor) {
+                    to_visit.push(neighbor);
+                }
+            }
+        }
+    }
+    connected
+}
+
+/// Returns the number of passages between tiles of the maze, counting each one once.
+pub fn open_passages(maze: &Maze) -> usize {
+    maze.iter()
+        .map(|(&pos, tile)| {
+            EdgeDirection::ALL_DIRECTIONS
+                .into_iter()
+                .filter(|&direction| {
+                    !tile.walls().contains(direction) && maze.neighbor(pos, direction).is_some()
+                })
+                .count()
+        })
+        .sum::<usize>()
+        / 2
+}
+
+/// Asserts that every tile is connected and the maze has no loops.
+pub fn assert_spanning_tree(maze: &Maze) {
+    let Some(&start) = maze.keys().next() else {
+        return;
+    };
+    assert_eq!(
+        connected_tiles(maze, start).len(),
+        maze.count(),
+        "All tiles should be connected"
+    );
+    assert_eq!(
+        open_passages(maze),
+        maze.count() - 1,
+        "Maze should be a spanning tree"
+    );
+}
+
+/// Returns the positions reachable from `start` through passages, stairs and tunnels.
+pub fn connected_levels(maze: &LayeredMaze, start: (Hex, usize)) -> HashSet<(Hex, usize)> {
+    let mut connected = HashSet::from([start]);
+    let mut to_visit = vec![start];
+    while let Some(current) = to_visit.pop() {
+        for next in maze.passages(&current) {
+            if connected.insert(next) {
+                to_visit.push(next);
+            }
+        }
+    }
+    connected
+}
+
+/// Returns the number of passages of a layered maze, counting each stair and tunnel once.
+pub fn layered_passages(maze: &LayeredMaze) -> usize {
+    maze.levels()
+        .iter()
+        .enumerate()
+        .flat_map(|(level, tiles)| tiles.keys().map(move |&pos| (pos, level)))
+        .map(|coord| maze.passages(&coord).len())
+        .sum::<usize>()
+        / 2
+}
+
+/// Asserts that every position of a layered maze is connected and the maze has no loops.
+pub fn assert_layered_spanning_tree(maze: &LayeredMaze) {
+    let Some(&start) = maze.levels().first().and_then(|level| level.keys().next()) else {
+        return;
+    };
+    assert_eq!(
+        connected_levels(maze, (start, 0)).len(),
+        maze.count(),
+        "All levels should be connected"
+    );
+    assert_eq!(
+        layered_passages(maze),
+        maze.count() - 1,
+        "Maze should be a spanning tree"
+    );
+}
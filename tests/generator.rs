@@ -1,3 +1,6 @@
+mod common;
+
+use common::open_passages;
 use hexlab::prelude::*;
 use rstest::rstest;
 
@@ -5,6 +8,31 @@ use rstest::rstest;
 #[case(GeneratorType::RecursiveBacktracking, None, None)]
 #[case(GeneratorType::RecursiveBacktracking, Some(Hex::new(1, -1)), None)]
 #[case(GeneratorType::RecursiveBacktracking, None, Some(12345))]
+#[case(GeneratorType::Prims, None, None)]
+#[case(GeneratorType::Prims, Some(Hex::new(1, -1)), None)]
+#[case(GeneratorType::Prims, None, Some(12345))]
+#[case(GeneratorType::Kruskal, None, None)]
+#[case(GeneratorType::Kruskal, Some(Hex::new(1, -1)), None)]
+#[case(GeneratorType::Kruskal, None, Some(12345))]
+#[case(GeneratorType::Wilson, None, None)]
+#[case(GeneratorType::Wilson, Some(Hex::new(1, -1)), None)]
+#[case(GeneratorType::Wilson, None, Some(12345))]
+#[case(GeneratorType::AldousBroder, None, None)]
+#[case(GeneratorType::AldousBroder, Some(Hex::new(1, -1)), None)]
+#[case(GeneratorType::AldousBroder, None, Some(12345))]
+#[case(GeneratorType::AldousBroderWilson { switch_at: 30 }, None, None)]
+#[case(GeneratorType::AldousBroderWilson { switch_at: 30 }, Some(Hex::new(1, -1)), None)]
+#[case(GeneratorType::AldousBroderWilson { switch_at: 30 }, None, Some(12345))]
+#[case(GeneratorType::GrowingTree { policy: GrowingTreePolicy::Newest }, None, None)]
+#[case(GeneratorType::GrowingTree { policy: GrowingTreePolicy::Oldest }, Some(Hex::new(1, -1)), None)]
+#[case(GeneratorType::GrowingTree { policy: GrowingTreePolicy::Random }, None, Some(12345))]
+#[case(GeneratorType::GrowingTree { policy: GrowingTreePolicy::Mixed { newest: 75 } }, None, Some(12345))]
+#[case(GeneratorType::HuntAndKill, None, None)]
+#[case(GeneratorType::HuntAndKill, Some(Hex::new(1, -1)), None)]
+#[case(GeneratorType::HuntAndKill, None, Some(12345))]
+#[case(GeneratorType::RecursiveDivision, None, None)]
+#[case(GeneratorType::RecursiveDivision, Some(Hex::new(1, -1)), None)]
+#[case(GeneratorType::RecursiveDivision, None, Some(12345))]
 fn generator_type(
     #[case] generator: GeneratorType,
     #[case] start_pos: Option<Hex>,
@@ -49,12 +77,50 @@ fn generator_type(
         let walls = maze.get_walls(&pos).unwrap();
         assert!(
             walls.count() < 6,
-            "Tile at {:?} should have at least one open wall",
-            pos
+            "Tile at {pos:?} should have at least one open wall"
         );
     }
 }
 
+#[rstest]
+#[case(GeneratorType::RecursiveBacktracking)]
+#[case(GeneratorType::Prims)]
+#[case(GeneratorType::Wilson)]
+#[case(GeneratorType::AldousBroder)]
+#[case(GeneratorType::AldousBroderWilson { switch_at: 30 })]
+#[case(GeneratorType::HuntAndKill)]
+fn generator_type_without_origin(#[case] generator: GeneratorType) {
+    // A ring of tiles around the origin, without the origin itself
+    let mut maze = Hex::ZERO.ring(2).collect::<Maze>();
+
+    generator.generate(&mut maze, None, Some(12345));
+
+    assert_eq!(
+        open_passages(&maze),
+        maze.count() - 1,
+        "Maze should be a spanning tree"
+    );
+}
+
+#[rstest]
+#[case(GeneratorType::RecursiveBacktracking)]
+#[case(GeneratorType::Prims)]
+#[case(GeneratorType::Wilson)]
+#[case(GeneratorType::AldousBroder)]
+#[case(GeneratorType::GrowingTree { policy: GrowingTreePolicy::Newest })]
+#[case(GeneratorType::HuntAndKill)]
+fn generator_type_start_outside_maze(#[case] generator: GeneratorType) {
+    let mut maze = Hex::ZERO.range(3).collect::<Maze>();
+
+    generator.generate(&mut maze, Some(Hex::new(10, 0)), Some(12345));
+
+    assert_eq!(
+        open_passages(&maze),
+        maze.count() - 1,
+        "Maze should be a spanning tree"
+    );
+}
+
 #[test]
 fn test_empty_maze() {
     let mut maze = Maze::new();
@@ -64,3 +130,182 @@ fn test_empty_maze() {
         "Empty maze should remain empty after generation"
     );
 }
+
+#[rstest]
+#[case(500)]
+#[case(1000)]
+fn large_maze_generation(#[case] radius: u16) {
+    let maze = MazeBuilder::new()
+        .with_radius(radius)
+        .with_seed(12345)
+        .build()
+        .expect("Failed to create maze");
+
+    let expected_size = 3 * usize::from(radius).pow(2) + 3 * usize::from(radius) + 1;
+    assert_eq!(maze.count(), expected_size);
+
+    // A perfect maze has exactly one passage less than it has tiles
+    assert_eq!(
+        open_passages(&maze),
+        maze.count() - 1,
+        "Maze should be a spanning tree"
+    );
+}
+
+#[rstest]
+#[case(GeneratorType::RecursiveBacktracking)]
+#[case(GeneratorType::Prims)]
+#[case(GeneratorType::Kruskal)]
+#[case(GeneratorType::Wilson)]
+#[case(GeneratorType::AldousBroder)]
+#[case(GeneratorType::AldousBroderWilson { switch_at: 30 })]
+#[case(GeneratorType::GrowingTree { policy: GrowingTreePolicy::Mixed { newest: 75 } })]
+#[case(GeneratorType::HuntAndKill)]
+#[case(GeneratorType::RecursiveDivision)]
+fn build_steps_matches_build(#[case] generator: GeneratorType) {
+    let builder = || {
+        MazeBuilder::new()
+            .with_radius(5)
+            .with_seed(12345)
+            .with_start_position(Hex::new(1, -1))
+            .with_generator(generator)
+    };
+    let maze = builder().build().expect("Failed to create maze");
+    let mut steps = builder().build_steps().expect("Failed to create maze");
+
+    assert!(steps.maze().values().all(|tile| tile.walls().is_enclosed()));
+    assert_eq!(steps.len(), maze.count() - 1, "Each step connects a tile");
+
+    let mut visited = 1;
+    while let Some(step) = steps.next() {
+        assert_eq!(step.from + step.direction, step.to);
+        visited = visited.max(steps.visited().len());
+        assert!(steps.frontier().is_disjoint(steps.visited()));
+    }
+    assert_eq!(visited, maze.count(), "All tiles should be visited");
+    assert_eq!(steps.into_maze(), maze);
+}
+
+#[test]
+fn build_steps_custom_generator() {
+    /// Delegates to a built-in generator, reporting steps in tile order.
+    struct Custom;
+
+    impl MazeGenerator for Custom {
+        fn generate(&self, maze: &mut Maze, start: Hex, rng: &mut dyn rand::RngCore) {
+            MazeGenerator::generate(&GeneratorType::Prims, maze, start, rng);
+        }
+    }
+
+    let maze = MazeBuilder::new()
+        .with_radius(5)
+        .with_seed(12345)
+        .with_custom_generator(Custom)
+        .build()
+        .expect("Failed to create maze");
+    let steps = MazeBuilder::new()
+        .with_radius(5)
+        .with_seed(12345)
+        .with_custom_generator(Custom)
+        .build_steps()
+        .expect("Failed to create maze");
+
+    assert_eq!(steps.len(), maze.count() - 1);
+    assert_eq!(steps.into_maze(), maze);
+}
+
+#[rstest]
+#[case(GeneratorType::RecursiveBacktracking)]
+#[case(GeneratorType::Prims)]
+#[case(GeneratorType::HuntAndKill)]
+fn build_steps_with_braid_matches_build(#[case] generator: GeneratorType) {
+    let builder = || {
+        MazeBuilder::new()
+            .with_radius(5)
+            .with_seed(12345)
+            .with_generator(generator)
+            .with_braid(0.5)
+    };
+    let maze = builder().build().expect("Failed to create maze");
+    let steps = builder().build_steps().expect("Failed to create maze");
+
+    assert!(
+        steps.len() > maze.count() - 1,
+        "Braiding carves extra passages"
+    );
+    assert_eq!(steps.into_maze(), maze);
+}
+
+#[test]
+fn build_steps_with_loops_matches_build() {
+    let builder = || {
+        MazeBuilder::new()
+            .with_radius(5)
+            .with_seed(12345)
+            .with_loops(6, 8)
+    };
+    let maze = builder().build().expect("Failed to create maze");
+    let steps = builder().build_steps().expect("Failed to create maze");
+
+    assert_eq!(steps.len(), maze.count() - 1 + 6);
+    assert_eq!(steps.into_maze(), maze);
+}
+
+#[test]
+fn build_steps_with_rooms_matches_build() {
+    let builder = || {
+        MazeBuilder::new()
+            .with_radius(6)
+            .with_seed(12345)
+            .with_room(Hex::new(2, -1).range(1), 2)
+            .with_room([Hex::new(-4, 0), Hex::new(-4, 1), Hex::new(-3, 1)], 1)
+    };
+    let maze = builder().build().expect("Failed to create maze");
+    let steps = builder().build_steps().expect("Failed to create maze");
+
+    assert_eq!(steps.into_maze(), maze);
+}
+
+#[rstest]
+#[case(GeneratorType::RecursiveBacktracking)]
+#[case(GeneratorType::Kruskal)]
+#[case(GeneratorType::Wilson)]
+fn build_steps_with_weave_matches_build(#[case] generator: GeneratorType) {
+    let builder = || {
+        MazeBuilder::new()
+            .with_radius(6)
+            .with_seed(12345)
+            .with_generator(generator)
+            .with_weave(0.5)
+    };
+    let maze = builder().build().expect("Failed to create maze");
+    let steps = builder().build_steps().expect("Failed to create maze");
+
+    // Every tunnel replaces a passage, so the maze stays perfect
+    assert_eq!(steps.len(), maze.count() - 1);
+    assert!(maze.values().any(|tile| tile.under_passage().is_some()));
+    assert_eq!(steps.into_maze(), maze);
+}
+
+#[test]
+fn build_steps_with_weave_and_loops_matches_build() {
+    let builder = || {
+        MazeBuilder::new()
+            .with_radius(6)
+            .with_seed(12345)
+            .with_weave(1.0)
+            .with_loops(20, 4)
+    };
+    let maze = builder().build().expect("Failed to create maze");
+    let steps = builder().build_steps().expect("Failed to create maze");
+
+    // Loops never open the walls of a crossed tile along its under-passage
+    assert!(maze.validate().is_valid());
+    for tile in maze.values() {
+        if let Some(direction) = tile.under_passage() {
+            assert!(tile.walls().contains(direction));
+            assert!(tile.walls().contains(direction.const_neg()));
+        }
+    }
+    assert_eq!(steps.into_maze(), maze);
+}
@@ -66,3 +66,258 @@ fn hex_maze_edge_cases() {
     assert!(maze.get(&non_existent).is_none());
     assert!(maze.get_walls(&non_existent).is_none());
 }
+
+#[test]
+fn hex_maze_open_shared_walls() {
+    let mut maze = Hex::ZERO.range(1).collect::<Maze>();
+    maze.open_shared_walls();
+
+    // The center tile shares all of its walls, each tile on the ring shares three
+    assert_eq!(maze.get_walls(&Hex::ZERO).map(Walls::count), Some(0));
+    for hex in Hex::ZERO.ring(1) {
+        assert_eq!(maze.get_walls(&hex).map(Walls::count), Some(3));
+    }
+}
+
+#[test]
+fn hex_maze_carve_and_wall_up_both_sides() {
+    let mut maze = Hex::ZERO.range(1).collect::<Maze>();
+
+    for direction in EdgeDirection::ALL_DIRECTIONS {
+        assert_eq!(maze.carve(&Hex::ZERO, direction), Ok(true));
+        assert_eq!(maze.carve(&Hex::ZERO, direction), Ok(false));
+        let neighbor = Hex::ZERO + direction;
+        assert!(!maze.get_walls(&neighbor).unwrap().contains(-direction));
+    }
+    assert_eq!(maze.get_walls(&Hex::ZERO).map(Walls::count), Some(0));
+
+    let direction = EdgeDirection::FLAT_NORTH;
+    assert_eq!(
+        maze.wall_up(&(Hex::ZERO + direction), -direction),
+        Ok(false)
+    );
+    assert_eq!(maze.wall_up(&Hex::ZERO, direction), Ok(true));
+    assert!(maze.get_walls(&Hex::ZERO).unwrap().contains(direction));
+}
+
+#[test]
+fn hex_maze_carve_missing_neighbor() {
+    let mut maze = Hex::ZERO.range(1).collect::<Maze>();
+    let edge = Hex::ZERO + EdgeDirection::FLAT_NORTH;
+
+    assert_eq!(
+        maze.carve(&edge, EdgeDirection::FLAT_NORTH),
+        Err(MazeError::NoNeighbor {
+            coord: edge,
+            direction: EdgeDirection::FLAT_NORTH
+        })
+    );
+    assert_eq!(
+        maze.wall_up(&Hex::new(5, 5), EdgeDirection::FLAT_NORTH),
+        Err(MazeError::InvalidCoordinate(Hex::new(5, 5)))
+    );
+    // Nothing is changed on failure
+    assert_eq!(maze.get_walls(&edge).map(Walls::count), Some(6));
+}
+
+#[test]
+fn hex_maze_carve_across_seam() {
+    let mut maze = Hex::ZERO.range(2).collect::<Maze>();
+    maze.set_topology(Topology::Hexagon { radius: 2 });
+    let edge = Hex::new(0, -2);
+    let direction = EdgeDirection::FLAT_NORTH;
+    let neighbor = maze.neighbor(edge, direction).unwrap();
+
+    assert_eq!(maze.carve(&edge, direction), Ok(true));
+    assert!(!maze.get_walls(&edge).unwrap().contains(direction));
+    assert!(!maze.get_walls(&neighbor).unwrap().contains(-direction));
+}
+
+#[test]
+fn hex_maze_validate_generated() {
+    let woven = MazeBuilder::new()
+        .with_radius(6)
+        .with_seed(12345)
+        .with_weave(1.0)
+        .build()
+        .unwrap();
+    assert_eq!(woven.validate(), ValidationReport::default());
+
+    let wrapping = MazeBuilder::new()
+        .with_radius(4)
+        .with_seed(12345)
+        .with_topology(Topology::Hexagon { radius: 4 })
+        .build()
+        .unwrap();
+    assert!(wrapping.validate().is_valid());
+}
+
+#[test]
+fn hex_maze_validate_reports_problems() {
+    let mut maze = Hex::ZERO.range(1).collect::<Maze>();
+    let edge = Hex::ZERO + EdgeDirection::FLAT_NORTH;
+    let _ = maze.remove_tile_wall(&Hex::ZERO, EdgeDirection::FLAT_SOUTH);
+    let _ = maze.remove_tile_wall(&edge, EdgeDirection::FLAT_NORTH);
+    // Stored under the wrong position through the inner map
+    let _ = (*maze).insert(Hex::new(-5, -5), Tile::new(Hex::new(5, 5)));
+
+    let report = maze.validate();
+    assert_eq!(
+        report.asymmetric_walls,
+        vec![(
+            Hex::ZERO + EdgeDirection::FLAT_SOUTH,
+            EdgeDirection::FLAT_NORTH
+        )]
+    );
+    assert_eq!(report.open_edges, vec![(edge, EdgeDirection::FLAT_NORTH)]);
+    assert_eq!(
+        report.misplaced_tiles,
+        vec![(Hex::new(-5, -5), Hex::new(5, 5))]
+    );
+    assert!(!report.is_valid());
+}
+
+#[test]
+fn hex_maze_repair() {
+    let mut maze = Hex::ZERO.range(1).collect::<Maze>();
+    let south = Hex::ZERO + EdgeDirection::FLAT_SOUTH;
+    let _ = maze.remove_tile_wall(&Hex::ZERO, EdgeDirection::FLAT_SOUTH);
+    let _ = maze.remove_tile_wall(&south, EdgeDirection::FLAT_SOUTH);
+
+    let mut closed = maze.clone();
+    let report = closed.repair(RepairPolicy::Close);
+    assert_eq!(report.asymmetric_walls.len(), 1);
+    assert_eq!(report.open_edges.len(), 1);
+    assert!(closed.validate().is_valid());
+    assert_eq!(closed, Hex::ZERO.range(1).collect::<Maze>());
+
+    let report = maze.repair(RepairPolicy::Open);
+    assert_eq!(report.asymmetric_walls.len(), 1);
+    assert!(maze.validate().is_valid());
+    assert!(!maze
+        .get_walls(&south)
+        .unwrap()
+        .contains(EdgeDirection::FLAT_NORTH));
+    assert!(maze
+        .get_walls(&south)
+        .unwrap()
+        .contains(EdgeDirection::FLAT_SOUTH));
+}
+
+#[test]
+fn hex_maze_edge_maze_round_trip() {
+    for shape in [
+        MazeShape::Hexagon,
+        MazeShape::Triangle,
+        MazeShape::Spiral { arm_width: 2 },
+    ] {
+        let maze = MazeBuilder::new()
+            .with_shape(shape)
+            .with_radius(6)
+            .with_seed(12345)
+            .with_loops(5, 4)
+            .build()
+            .unwrap();
+        let edges = EdgeMaze::from(&maze);
+
+        assert_eq!(edges.count(), maze.count());
+        for (hex, walls) in edges.iter() {
+            assert_eq!(maze.get_walls(&hex), Some(&walls));
+        }
+        assert_eq!(Maze::from(&edges), maze);
+    }
+}
+
+#[cfg(feature = "pathfinding")]
+#[test]
+fn hex_maze_tile_data() {
+    let maze = MazeBuilder::new()
+        .with_radius(4)
+        .with_seed(12345)
+        .build()
+        .unwrap();
+    let mut visits = maze.clone().map_data(|_, ()| 0_u32);
+
+    let path = visits.find_path(Hex::ZERO, Hex::new(-2, 4)).unwrap();
+    for pos in &path {
+        *visits.data_mut(pos).unwrap() += 1;
+    }
+
+    assert_eq!(visits.count(), maze.count());
+    assert_eq!(visits.topology(), maze.topology());
+    for (pos, tile) in visits.iter() {
+        assert_eq!(tile.walls(), maze.get(pos).unwrap().walls());
+        assert_eq!(*tile.data(), u32::from(path.contains(pos)));
+    }
+    assert_eq!(visits.map_data(|_, _| ()), maze);
+}
+
+#[test]
+fn hex_maze_collect_with_tile_data() {
+    let maze = Hex::ZERO.range(2).collect::<Maze<u32>>();
+
+    assert_eq!(maze.count(), 19);
+    for (&pos, tile) in maze.iter() {
+        assert_eq!(tile.pos(), pos);
+        assert_eq!(tile.walls(), &Walls::default());
+        assert_eq!(*tile.data(), 0);
+    }
+    assert_eq!(
+        maze.map_data(|_, _| ()),
+        Hex::ZERO.range(2).collect::<Maze>()
+    );
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn hex_maze_serde_baseline_format() {
+    // A maze of radius 1 serialized to RON by hexlab 0.6
+    const BASELINE: &str = "({(x:1,y:0):(pos:(x:1,y:0),walls:(39)),(x:-1,y:0):(pos:(x:-1,y:0),\
+        walls:(29)),(x:0,y:-1):(pos:(x:0,y:-1),walls:(58)),(x:0,y:1):(pos:(x:0,y:1),walls:(55)),\
+        (x:-1,y:1):(pos:(x:-1,y:1),walls:(46)),(x:1,y:-1):(pos:(x:1,y:-1),walls:(53)),\
+        (x:0,y:0):(pos:(x:0,y:0),walls:(62))})";
+
+    let maze: Maze = ron::from_str(BASELINE).expect("Failed to load baseline maze");
+    assert_eq!(maze.count(), 7);
+    assert_eq!(maze.topology(), Topology::Bounded);
+    assert_eq!(maze.get_walls(&Hex::ZERO).map(Walls::count), Some(5));
+    assert!(maze.validate().is_valid());
+
+    let serialized = ron::to_string(&maze).expect("Failed to serialize maze");
+    let loaded: Maze = ron::from_str(&serialized).expect("Failed to load maze");
+    assert_eq!(loaded, maze);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn hex_maze_serde_wrapping_topology() {
+    let maze = MazeBuilder::new()
+        .with_radius(2)
+        .with_topology(Topology::Hexagon { radius: 2 })
+        .with_seed(12345)
+        .build()
+        .expect("Failed to create maze");
+
+    let serialized = ron::to_string(&maze).expect("Failed to serialize maze");
+    let loaded: Maze = ron::from_str(&serialized).expect("Failed to load maze");
+    assert_eq!(loaded.topology(), Topology::Hexagon { radius: 2 });
+    assert_eq!(loaded, maze);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn hex_maze_serde_non_self_describing() {
+    for topology in [Topology::Bounded, Topology::Hexagon { radius: 3 }] {
+        let maze = MazeBuilder::new()
+            .with_radius(3)
+            .with_topology(topology)
+            .with_seed(12345)
+            .build()
+            .expect("Failed to create maze");
+
+        let serialized = bincode::serialize(&maze).expect("Failed to serialize maze");
+        let loaded: Maze = bincode::deserialize(&serialized).expect("Failed to load maze");
+        assert_eq!(loaded.topology(), topology);
+        assert_eq!(loaded, maze);
+    }
+}
@@ -1,4 +1,9 @@
+#![cfg(feature = "pathfinding")]
+
+mod common;
+
 use claims::*;
+use common::assert_layered_spanning_tree;
 use hexlab::MazeBuilder;
 use hexx::{hex, EdgeDirection, Hex};
 
@@ -77,3 +82,106 @@ fn path_in_larger_maze() {
             .any(|&dir| current.neighbor(dir) == next));
     }
 }
+
+#[test]
+fn path_across_seam() {
+    use hexlab::prelude::*;
+
+    let mut maze = assert_ok!(MazeBuilder::new()
+        .with_shape(MazeShape::Parallelogram)
+        .with_dimensions(6, 6)
+        .with_topology(Topology::Parallelogram {
+            width: 6,
+            height: 6
+        })
+        .build());
+    // Open every passage, so the shortest path crosses the seam
+    maze.open_shared_walls();
+
+    let start = Hex::new(-2, 0);
+    let goal = Hex::new(3, 0);
+    assert_some_eq!(maze.find_path(start, goal), vec![start, goal]);
+}
+
+#[test]
+fn layered_path_uses_stairs() {
+    use hexlab::prelude::*;
+
+    let maze = assert_ok!(MazeBuilder::new()
+        .with_seed(12345)
+        .with_radius(3)
+        .with_levels(3)
+        .build_layered());
+
+    let path = assert_some!(maze.find_path((Hex::ZERO, 0), (Hex::ZERO, 2)));
+    assert_eq!(path.first(), Some(&(Hex::ZERO, 0)));
+    assert_eq!(path.last(), Some(&(Hex::ZERO, 2)));
+    for pair in path.windows(2) {
+        assert!(maze.passages(&pair[0]).contains(&pair[1]));
+        let ((pos, level), (next_pos, next_level)) = (pair[0], pair[1]);
+        if level != next_level {
+            assert_eq!(pos, next_pos, "Path should climb stairs in place");
+            assert!(maze.has_stairs_up(&(pos, level.min(next_level))));
+        }
+    }
+}
+
+#[test]
+fn path_under_crossing() {
+    use hexlab::prelude::*;
+
+    // A corridor along the x axis, crossed by a tunnel under the origin along the y axis
+    let mut maze = Maze::from([hex(-1, 0), Hex::ZERO, hex(1, 0), hex(0, -1), hex(0, 1)]);
+    let east = EdgeDirection::FLAT_SOUTH_EAST;
+    let south = EdgeDirection::FLAT_SOUTH;
+    assert_eq!(Hex::ZERO + east, hex(1, 0));
+    assert_eq!(Hex::ZERO + south, hex(0, 1));
+    for (pos, direction) in [(hex(-1, 0), east), (hex(1, 0), -east)] {
+        assert_ok!(maze.remove_tile_wall(&pos, direction));
+        assert_ok!(maze.remove_tile_wall(&Hex::ZERO, -direction));
+    }
+    assert_ok!(maze.remove_tile_wall(&hex(0, -1), south));
+    assert_ok!(maze.remove_tile_wall(&hex(0, 1), -south));
+    assert_some!(maze.get_mut(&Hex::ZERO)).set_under_passage(Some(south));
+
+    // Over and under passages are separate routes through the same tile
+    assert_some_eq!(
+        maze.find_path(hex(0, -1), hex(0, 1)),
+        vec![hex(0, -1), Hex::ZERO, hex(0, 1)]
+    );
+    assert_some_eq!(
+        maze.find_path(hex(-1, 0), hex(1, 0)),
+        vec![hex(-1, 0), Hex::ZERO, hex(1, 0)]
+    );
+    assert_none!(maze.find_path(hex(0, -1), hex(1, 0)));
+    assert_none!(maze.find_path(hex(0, -1), Hex::ZERO));
+}
+
+#[test]
+fn layered_path_under_crossing() {
+    use hexlab::prelude::*;
+
+    let maze = assert_ok!(MazeBuilder::new()
+        .with_seed(12345)
+        .with_radius(4)
+        .with_levels(2)
+        .with_weave(1.0)
+        .build_layered());
+    let level = assert_some!(maze.level(0));
+    assert!(level.values().any(|tile| tile.under_passage().is_some()));
+
+    // Tunnels and stairs connect every tile, each tunnel counting as a single passage
+    assert_layered_spanning_tree(&maze);
+
+    // Paths within a level pass under tiles just like on the level on its own, and the tree
+    // leaves no other route through the remaining levels
+    for &target in level.keys() {
+        let path = assert_some!(maze.find_path((Hex::ZERO, 0), (target, 0)));
+        if let Some(expected) = level.find_path(Hex::ZERO, target) {
+            assert_eq!(
+                path,
+                expected.into_iter().map(|pos| (pos, 0)).collect::<Vec<_>>()
+            );
+        }
+    }
+}